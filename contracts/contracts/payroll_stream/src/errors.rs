@@ -27,4 +27,52 @@ pub enum StreamError {
     InvalidStartTime = 10,
     /// The recipient address is invalid or same as sender.
     InvalidRecipient = 11,
+    /// The stream is not active and cannot be paused.
+    StreamNotActive = 12,
+    /// The stream is not paused and cannot be resumed.
+    StreamNotPaused = 13,
+    /// The sender has locked this stream against recipient transfers.
+    StreamNotTransferable = 14,
+    /// The operation is not supported for this kind of stream.
+    InvalidStreamKind = 15,
+    /// The cliff time falls outside the stream's start and end times.
+    InvalidCliff = 16,
+    /// The recipient has opted out of payouts triggered by third parties.
+    PushOptedOut = 17,
+    /// Payout splits are empty shares or add up to more than 100%.
+    InvalidSplits = 18,
+    /// The stream's cancel policy does not allow the sender to stop it.
+    NotCancelable = 19,
+    /// Cancellation was requested and the notice period has not yet run out.
+    NoticePeriodActive = 20,
+    /// The operator's budget for this token does not cover the stream.
+    BudgetExceeded = 21,
+    /// The stream has been archived and must be restored before use.
+    StreamArchived = 22,
+    /// The stream still holds escrow and cannot be archived.
+    StreamStillLive = 23,
+    /// The supplied record does not match the archived stream.
+    InvalidArchiveRecord = 24,
+    /// No milestone exists at the given index.
+    MilestoneNotFound = 25,
+    /// The milestone has already been settled or is in the wrong state for this action.
+    InvalidMilestoneState = 26,
+    /// The milestone's review period has not yet run out.
+    MilestoneNotDue = 27,
+    /// The advance would exceed the stream's advance limit.
+    AdvanceLimitExceeded = 28,
+    /// The fee configuration is out of range.
+    InvalidFeeConfig = 29,
+    /// The stream's current period has not ended yet.
+    RenewalNotDue = 30,
+    /// Renewal is turned off, paused for lack of funds, or the stream is being cancelled.
+    RenewalInactive = 31,
+    /// The stream has no arbiter, or the arbiter is a party to the stream.
+    InvalidArbiter = 32,
+    /// The stream is on hold pending the arbiter's decision.
+    StreamOnHold = 33,
+    /// The stream is not on hold.
+    StreamNotHeld = 34,
+    /// An unrepaid salary advance blocks changes to the stream's escrow.
+    AdvanceOutstanding = 35,
 }
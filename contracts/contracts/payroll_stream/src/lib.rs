@@ -1,17 +1,35 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, Env, Vec, symbol_short, token};
+use soroban_sdk::{
+    contract, contractimpl, xdr::ToXdr, Address, BytesN, Env, Map, Vec, symbol_short, token,
+};
 
 mod errors;
 mod storage;
+pub mod swap;
 mod types;
 
 use errors::StreamError;
 use storage::{
     get_admin, has_admin, set_admin, get_stream_count, set_stream_count,
     get_stream, set_stream, add_sender_stream, add_recipient_stream,
-    get_sender_streams, get_recipient_streams,
+    get_sender_streams, get_recipient_streams, remove_recipient_stream,
+    get_push_opt_out, set_push_opt_out, is_operator, set_operator,
+    get_operator_budget, set_operator_budget, get_index_page,
+    StreamIndex, extend_instance_ttl, extend_stream_ttl, remove_stream,
+    get_archived_stream, set_archived_stream, remove_archived_stream, get_swap_router,
+    set_swap_router, add_claim_record, get_claim_count, get_claim_record, get_fee_config,
+    set_fee_config, get_accrued_fees, set_accrued_fees,
+};
+use swap::SwapRouterClient;
+use types::{
+    CancelPolicy, ClaimHistoryPage, ClaimPage, ClaimRecord, CreateStreamParams, FeeConfig, FeeMode,
+    FeeTiming, Milestone, MilestoneParams,
+    MilestoneStatus, MilestoneTerms, MilestoneTimeout, OperatorBudget, PaySchedule, PayoutSplit,
+    PayrollStream, Renewal, RenewalFunding, SettlementReport, StreamClaim, StreamKind,
+    StreamPage, StreamSegment,
+    StreamSettlement, StreamStatus, StreamSummary, DISPUTE_TIMEOUT, MAX_BPS, MAX_PAGE_SIZE,
+    MAX_PAYOUT_RATE, MAX_SPLITS, MAX_WITHHOLDING_BPS, PRICE_SCALE,
 };
-use types::{PayrollStream, StreamStatus, CreateStreamParams};
 
 #[contract]
 pub struct PayrollStreamContract;
@@ -37,6 +55,7 @@ impl PayrollStreamContract {
 
     /// Create a new payment stream to an employee/recipient.
     /// Tokens are linearly streamed from `start_time` to `end_time`.
+    /// The full `total_amount` is escrowed from the sender up front.
     pub fn create_stream(
         env: Env,
         sender: Address,
@@ -45,57 +64,194 @@ impl PayrollStreamContract {
         total_amount: i128,
         start_time: u64,
         end_time: u64,
+    ) -> Result<u32, StreamError> {
+        let params = CreateStreamParams {
+            recipient,
+            token,
+            total_amount,
+            start_time,
+            end_time,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        };
+        Self::create_stream_with_params(env, sender, params)
+    }
+
+    /// Create a new payment stream from a full set of parameters, including an optional
+    /// cliff. Before the cliff nothing is claimable; at the cliff everything accrued so far
+    /// unlocks at once.
+    pub fn create_stream_with_params(
+        env: Env,
+        sender: Address,
+        params: CreateStreamParams,
     ) -> Result<u32, StreamError> {
         if !has_admin(&env) {
             return Err(StreamError::NotInitialized);
         }
         sender.require_auth();
 
-        if sender == recipient {
-            return Err(StreamError::InvalidRecipient);
-        }
-        if total_amount <= 0 {
+        let stream_id = get_stream_count(&env);
+        let stream = Self::build_stream(&env, &sender, stream_id, &params)?;
+        Self::open_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("s_create"), sender.clone()),
+            stream_id,
+        );
+
+        Ok(stream_id)
+    }
+
+    /// Grant `operator` the right to create and manage streams funded by the org account
+    /// (the admin), spending at most `limit` of `token` in total on escrow and creation fees.
+    /// Calling again updates the limit without resetting what has already been spent.
+    /// The org account must approve this contract as a spender of `token`.
+    pub fn grant_operator(
+        env: Env,
+        admin: Address,
+        operator: Address,
+        token: Address,
+        limit: i128,
+    ) -> Result<(), StreamError> {
+        Self::require_admin(&env, &admin)?;
+        if limit < 0 {
             return Err(StreamError::InvalidAmount);
         }
-        if end_time <= start_time {
-            return Err(StreamError::InvalidDuration);
+
+        let mut budget = get_operator_budget(&env, &operator, &token);
+        budget.limit = limit;
+        set_operator_budget(&env, &operator, &token, &budget);
+        set_operator(&env, &operator, true);
+
+        env.events().publish(
+            (symbol_short!("op_grant"), operator),
+            (token, limit),
+        );
+
+        Ok(())
+    }
+
+    /// Configure the swap router used to pay recipients in a different token.
+    /// Restricted to admin.
+    pub fn set_swap_router(env: Env, admin: Address, router: Address) -> Result<(), StreamError> {
+        Self::require_admin(&env, &admin)?;
+
+        set_swap_router(&env, &router);
+
+        env.events().publish((symbol_short!("router"),), router);
+
+        Ok(())
+    }
+
+    /// Set the fee charged on streams created from now on. Existing streams keep the fee
+    /// they were created with. Restricted to admin.
+    pub fn set_fee_config(env: Env, admin: Address, config: FeeConfig) -> Result<(), StreamError> {
+        Self::require_admin(&env, &admin)?;
+
+        match config.mode {
+            FeeMode::Flat(amount) if amount < 0 => return Err(StreamError::InvalidFeeConfig),
+            FeeMode::Proportional(bps) if bps > MAX_BPS => {
+                return Err(StreamError::InvalidFeeConfig)
+            }
+            _ => {}
         }
 
-        let duration = end_time - start_time;
-        let rate_per_second = total_amount / (duration as i128);
+        set_fee_config(&env, &config);
 
-        let stream_id = get_stream_count(&env);
-        let stream = PayrollStream {
-            id: stream_id,
-            sender: sender.clone(),
-            recipient: recipient.clone(),
-            token,
-            total_amount,
-            claimed_amount: 0,
-            start_time,
-            end_time,
-            last_claim_time: start_time,
-            status: StreamStatus::Active,
-            rate_per_second,
-        };
+        env.events().publish((symbol_short!("fee_cfg"),), config.collector);
+
+        Ok(())
+    }
+
+    /// Send all accrued fees in `token` to the fee collector. Only the collector can withdraw.
+    pub fn withdraw_fees(
+        env: Env,
+        collector: Address,
+        token: Address,
+    ) -> Result<i128, StreamError> {
+        let config = get_fee_config(&env).ok_or(StreamError::NotInitialized)?;
+        collector.require_auth();
 
-        // TODO: Transfer total_amount from sender to contract (contributor task SC-10)
-        // token::Client::new(&env, &token).transfer(&sender, &env.current_contract_address(), &total_amount);
+        if config.collector != collector {
+            return Err(StreamError::Unauthorized);
+        }
 
-        set_stream(&env, stream_id, &stream);
-        set_stream_count(&env, stream_id + 1);
-        add_sender_stream(&env, &sender, stream_id);
-        add_recipient_stream(&env, &recipient, stream_id);
+        let amount = get_accrued_fees(&env, &token);
+        if amount <= 0 {
+            return Err(StreamError::NothingToClaim);
+        }
+
+        set_accrued_fees(&env, &token, 0);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &collector,
+            &amount,
+        );
 
         env.events().publish(
-            (symbol_short!("s_create"), sender.clone()),
-            stream_id,
+            (symbol_short!("fee_wd"), collector),
+            (token, amount),
+        );
+
+        Ok(amount)
+    }
+
+    /// Remove an operator's role. Streams it created stay with the org.
+    pub fn revoke_operator(env: Env, admin: Address, operator: Address) -> Result<(), StreamError> {
+        Self::require_admin(&env, &admin)?;
+
+        set_operator(&env, &operator, false);
+
+        env.events().publish((symbol_short!("op_revoke"), operator), ());
+
+        Ok(())
+    }
+
+    /// Create a stream on behalf of the org. The org account (the admin) is the stream's
+    /// sender and funds the escrow through its token allowance to this contract; the
+    /// operator is recorded on the stream and charged against its budget.
+    pub fn create_stream_as_operator(
+        env: Env,
+        operator: Address,
+        params: CreateStreamParams,
+    ) -> Result<u32, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        operator.require_auth();
+
+        if !is_operator(&env, &operator) {
+            return Err(StreamError::Unauthorized);
+        }
+
+        let org = get_admin(&env);
+        let stream_id = get_stream_count(&env);
+        let mut stream = Self::build_stream(&env, &org, stream_id, &params)?;
+        stream.operator = operator.clone();
+
+        // The org pays the creation fee too, so it counts against the budget
+        let fee = Self::creation_fee(&stream, stream.total_amount);
+        let mut budget = get_operator_budget(&env, &operator, &stream.token);
+        budget.spent = budget
+            .spent
+            .checked_add(stream.total_amount)
+            .and_then(|spent| spent.checked_add(fee))
+            .filter(|spent| *spent <= budget.limit)
+            .ok_or(StreamError::BudgetExceeded)?;
+        set_operator_budget(&env, &operator, &stream.token, &budget);
+        Self::open_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("op_create"), operator),
+            (org, stream_id),
         );
 
         Ok(stream_id)
     }
 
     /// Create multiple payment streams in a single transaction.
+    /// Escrow is pulled with one aggregated transfer per token.
     /// Emits a single batch event with all created stream IDs.
     pub fn create_batch_streams(
         env: Env,
@@ -108,54 +264,26 @@ impl PayrollStreamContract {
         sender.require_auth();
 
         let mut stream_ids: Vec<u32> = Vec::new(&env);
-        let mut count = get_stream_count(&env);
+        let mut escrow: Map<Address, i128> = Map::new(&env);
 
         for stream_params in streams.iter() {
-            let recipient = stream_params.recipient;
-            let token = stream_params.token;
-            let total_amount = stream_params.total_amount;
-            let start_time = stream_params.start_time;
-            let end_time = stream_params.end_time;
-
-            if sender == recipient {
-                return Err(StreamError::InvalidRecipient);
-            }
-            if total_amount <= 0 {
-                return Err(StreamError::InvalidAmount);
-            }
-            if end_time <= start_time {
-                return Err(StreamError::InvalidDuration);
-            }
+            let stream_id = get_stream_count(&env);
+            let stream = Self::build_stream(&env, &sender, stream_id, &stream_params)?;
 
-            let duration = end_time - start_time;
-            let rate_per_second = total_amount / (duration as i128);
-
-            let stream_id = count;
-            let stream = PayrollStream {
-                id: stream_id,
-                sender: sender.clone(),
-                recipient: recipient.clone(),
-                token: token.clone(),
-                total_amount,
-                claimed_amount: 0,
-                start_time,
-                end_time,
-                last_claim_time: start_time,
-                status: StreamStatus::Active,
-                rate_per_second,
-            };
+            let fee = Self::creation_fee(&stream, stream.total_amount);
+            Self::accrue_fee(&env, &stream.token, fee);
+            let owed = escrow.get(stream.token.clone()).unwrap_or(0);
+            escrow.set(stream.token.clone(), owed + stream.total_amount + fee);
+
+            Self::store_new_stream(&env, &stream);
 
-            // TODO: Transfer total_amount from sender to contract (batch transfer optimization possible)
-            
-            set_stream(&env, stream_id, &stream);
-            add_sender_stream(&env, &sender, stream_id);
-            add_recipient_stream(&env, &recipient, stream_id);
-            
             stream_ids.push_back(stream_id);
-            count += 1;
         }
 
-        set_stream_count(&env, count);
+        let contract_addr = env.current_contract_address();
+        for (token, amount) in escrow.iter() {
+            token::Client::new(&env, &token).transfer(&sender, &contract_addr, &amount);
+        }
 
         env.events().publish(
             (symbol_short!("b_create"), sender.clone()),
@@ -165,174 +293,2544 @@ impl PayrollStreamContract {
         Ok(stream_ids)
     }
 
-    /// Claim accrued tokens from an active stream.
-    /// The recipient can claim at any point — they receive tokens proportional to elapsed time.
-    pub fn claim(env: Env, recipient: Address, stream_id: u32) -> Result<i128, StreamError> {
+    /// Create a stream that pays `total_amount` in equal installments on each payday
+    /// between `start_time` and `end_time`, instead of accruing per second.
+    pub fn create_tranche_stream(
+        env: Env,
+        sender: Address,
+        params: CreateStreamParams,
+        schedule: PaySchedule,
+    ) -> Result<u32, StreamError> {
         if !has_admin(&env) {
             return Err(StreamError::NotInitialized);
         }
-        recipient.require_auth();
-
-        let mut stream = get_stream(&env, stream_id)
-            .ok_or(StreamError::StreamNotFound)?;
+        sender.require_auth();
 
-        if stream.recipient != recipient {
-            return Err(StreamError::Unauthorized);
-        }
-        if stream.status == StreamStatus::Cancelled {
-            return Err(StreamError::StreamAlreadyCancelled);
+        if schedule.period.seconds() == 0 {
+            return Err(StreamError::InvalidDuration);
         }
-        if stream.status == StreamStatus::Completed {
-            return Err(StreamError::StreamCompleted);
+
+        let stream_id = get_stream_count(&env);
+        let mut stream = Self::build_stream(&env, &sender, stream_id, &params)?;
+        stream.kind = StreamKind::Tranche(schedule);
+        Self::open_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("t_create"), sender.clone()),
+            stream_id,
+        );
+
+        Ok(stream_id)
+    }
+
+    /// Create a milestone stream for contractor work. The milestones' amounts are escrowed
+    /// up front and each is released when the sender or the reviewer approves it.
+    /// Milestones nobody reviews are settled by `terms.on_timeout` once their review
+    /// period has passed.
+    pub fn create_milestone_stream(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        milestones: Vec<MilestoneParams>,
+        terms: MilestoneTerms,
+    ) -> Result<u32, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
         }
+        sender.require_auth();
 
-        let claimable = Self::calculate_claimable(&env, &stream);
-        if claimable <= 0 {
-            return Err(StreamError::NothingToClaim);
+        if milestones.is_empty() {
+            return Err(StreamError::InvalidAmount);
         }
 
-        stream.claimed_amount += claimable;
         let now = env.ledger().timestamp();
-        stream.last_claim_time = now;
-
-        // Check if stream is fully claimed
-        if stream.claimed_amount >= stream.total_amount {
-            stream.status = StreamStatus::Completed;
+        let mut total_amount: i128 = 0;
+        let mut end_time = now;
+        let mut plan: Vec<Milestone> = Vec::new(&env);
+        for params in milestones.iter() {
+            if params.amount <= 0 {
+                return Err(StreamError::InvalidAmount);
+            }
+            if params.due_date <= now {
+                return Err(StreamError::InvalidDuration);
+            }
+            total_amount += params.amount;
+            end_time = end_time.max(params.due_date);
+            plan.push_back(Milestone {
+                amount: params.amount,
+                description_hash: params.description_hash,
+                due_date: params.due_date,
+                status: MilestoneStatus::Pending,
+            });
         }
 
-        // TODO: Transfer claimable tokens to recipient (contributor task SC-11)
-        // token::Client::new(&env, &stream.token)
-        //     .transfer(&env.current_contract_address(), &recipient, &claimable);
-
-        set_stream(&env, stream_id, &stream);
+        let stream_id = get_stream_count(&env);
+        let params = CreateStreamParams {
+            recipient,
+            token,
+            total_amount,
+            start_time: now,
+            end_time,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        };
+        let mut stream = Self::build_stream(&env, &sender, stream_id, &params)?;
+        stream.kind = StreamKind::Milestone(terms, plan);
+        stream.segments = Vec::new(&env);
+        stream.rate_per_second = 0;
+        Self::open_stream(&env, &stream);
 
         env.events().publish(
-            (symbol_short!("claim"), recipient.clone()),
-            claimable,
+            (symbol_short!("m_create"), sender.clone()),
+            stream_id,
         );
 
-        Ok(claimable)
+        Ok(stream_id)
     }
 
-    /// Cancel a stream. Only the sender (organization) can cancel.
-    /// Unclaimed tokens are returned to the sender. Already-claimed tokens stay with recipient.
-    pub fn cancel_stream(
+    /// Approve a milestone and release it to the recipient. Only the sender or the
+    /// reviewer can approve; this also settles a disputed milestone in the recipient's favour.
+    pub fn approve_milestone(
         env: Env,
-        sender: Address,
+        caller: Address,
         stream_id: u32,
+        index: u32,
     ) -> Result<(), StreamError> {
         if !has_admin(&env) {
             return Err(StreamError::NotInitialized);
         }
-        sender.require_auth();
+        caller.require_auth();
 
         let mut stream = get_stream(&env, stream_id)
             .ok_or(StreamError::StreamNotFound)?;
-
-        if stream.sender != sender {
+        let StreamKind::Milestone(ref terms, _) = stream.kind else {
+            return Err(StreamError::InvalidStreamKind);
+        };
+        if caller != stream.sender && caller != terms.reviewer {
             return Err(StreamError::Unauthorized);
         }
-        if stream.status == StreamStatus::Cancelled {
-            return Err(StreamError::StreamAlreadyCancelled);
+
+        Self::resolve_milestone(&env, stream_id, &mut stream, index, true)
+    }
+
+    /// Reject a milestone and refund it to the sender. Only the reviewer can reject.
+    pub fn reject_milestone(
+        env: Env,
+        reviewer: Address,
+        stream_id: u32,
+        index: u32,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
         }
-        if stream.status == StreamStatus::Completed {
-            return Err(StreamError::StreamCompleted);
+        reviewer.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+        let StreamKind::Milestone(ref terms, _) = stream.kind else {
+            return Err(StreamError::InvalidStreamKind);
+        };
+        if reviewer != terms.reviewer {
+            return Err(StreamError::Unauthorized);
         }
 
-        // Calculate what recipient is owed up to now
-        let claimable = Self::calculate_claimable(&env, &stream);
-        let refund = stream.total_amount - stream.claimed_amount - claimable;
+        Self::resolve_milestone(&env, stream_id, &mut stream, index, false)
+    }
 
-        stream.status = StreamStatus::Cancelled;
-        let contract_addr = env.current_contract_address();
-        let token_client = token::Client::new(&env, &stream.token);
+    /// Dispute a pending milestone. It waits for the reviewer to approve or reject it, and
+    /// only settles on timeout once `DISPUTE_TIMEOUT` has passed after its review period.
+    /// Either the sender or the recipient can dispute.
+    pub fn dispute_milestone(
+        env: Env,
+        caller: Address,
+        stream_id: u32,
+        index: u32,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        caller.require_auth();
 
-        if claimable > 0 {
-            token_client.transfer(&contract_addr, &stream.recipient, &claimable);
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+        if caller != stream.sender && caller != stream.recipient {
+            return Err(StreamError::Unauthorized);
         }
-        if refund > 0 {
-            token_client.transfer(&contract_addr, &sender, &refund);
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        let StreamKind::Milestone(_, ref mut milestones) = stream.kind else {
+            return Err(StreamError::InvalidStreamKind);
+        };
+
+        let mut milestone = milestones.get(index)
+            .ok_or(StreamError::MilestoneNotFound)?;
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(StreamError::InvalidMilestoneState);
         }
+        milestone.status = MilestoneStatus::Disputed;
+        milestones.set(index, milestone);
 
         set_stream(&env, stream_id, &stream);
 
         env.events().publish(
-            (symbol_short!("cancel"), sender.clone()),
-            stream_id,
+            (symbol_short!("m_dispute"), caller),
+            (stream_id, index),
         );
 
         Ok(())
     }
 
-    // ── Internal Helpers ─────────────────────────────────────────
+    /// Settle a pending milestone whose review period has run out, releasing or refunding
+    /// it according to the stream's timeout policy. A disputed milestone the reviewer has not
+    /// decided settles the same way once a further `DISPUTE_TIMEOUT` has passed.
+    /// Anyone can call this.
+    pub fn settle_milestone(env: Env, stream_id: u32, index: u32) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
 
-    /// Calculate the amount of tokens claimable by the recipient at the current time.
-    fn calculate_claimable(env: &Env, stream: &PayrollStream) -> i128 {
-        let now = env.ledger().timestamp();
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+        let StreamKind::Milestone(ref terms, ref milestones) = stream.kind else {
+            return Err(StreamError::InvalidStreamKind);
+        };
 
-        if now <= stream.start_time {
-            return 0;
+        let milestone = milestones.get(index)
+            .ok_or(StreamError::MilestoneNotFound)?;
+        if milestone.status != MilestoneStatus::Pending
+            && milestone.status != MilestoneStatus::Disputed
+        {
+            return Err(StreamError::InvalidMilestoneState);
+        }
+        if !Self::milestone_timed_out(&env, terms, &milestone) {
+            return Err(StreamError::MilestoneNotDue);
         }
 
-        let effective_time = if now >= stream.end_time {
-            stream.end_time
-        } else {
-            now
-        };
+        let release = terms.on_timeout == MilestoneTimeout::AutoRelease;
+        Self::resolve_milestone(&env, stream_id, &mut stream, index, release)
+    }
 
-        let elapsed = effective_time - stream.start_time;
-        // Check if stream is completed to avoid division by zero (though duration checked at creation)
-        if stream.end_time <= stream.start_time {
-             return 0; 
+    /// Create an open-ended flow stream paying `rate_per_second` from `start_time`.
+    /// `deposit` is escrowed up front; the recipient accrues until it runs out, after
+    /// which unpaid accrual is recorded as debt until the sender deposits more.
+    pub fn create_flow_stream(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        rate_per_second: i128,
+        start_time: u64,
+        deposit: i128,
+    ) -> Result<u32, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
         }
-        
-        // Recalculate based on total amount and duration to minimize rounding errors
-        // Instead of using stored rate_per_second which might have rounding loss
-        let duration = stream.end_time - stream.start_time;
-        let total_accrued = (stream.total_amount * (elapsed as i128)) / (duration as i128);
+        sender.require_auth();
 
-        // Clamp to total_amount
-        let total_accrued = if total_accrued > stream.total_amount {
-            stream.total_amount
-        } else {
-            total_accrued
-        };
-        
-        // Ensure we don't return negative claimable if something is wrong with state
-        if total_accrued < stream.claimed_amount {
-            return 0;
+        if rate_per_second <= 0 {
+            return Err(StreamError::InvalidAmount);
         }
 
-        total_accrued - stream.claimed_amount
-    }
+        let stream_id = get_stream_count(&env);
+        let params = CreateStreamParams {
+            recipient,
+            token,
+            total_amount: deposit,
+            start_time,
+            end_time: u64::MAX,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        };
+        let mut stream = Self::build_stream(&env, &sender, stream_id, &params)?;
+        stream.kind = StreamKind::Flow;
+        stream.segments = Vec::new(&env);
+        stream.rate_per_second = rate_per_second;
+        Self::open_stream(&env, &stream);
 
-    // ── Query Functions ──────────────────────────────────────────
+        env.events().publish(
+            (symbol_short!("f_create"), sender.clone()),
+            stream_id,
+        );
 
-    /// Get a specific stream by ID.
-    pub fn get_stream(env: Env, stream_id: u32) -> Result<PayrollStream, StreamError> {
-        get_stream(&env, stream_id).ok_or(StreamError::StreamNotFound)
+        Ok(stream_id)
     }
 
-    /// Get the claimable balance for a stream at the current time.
-    pub fn get_claimable(env: Env, stream_id: u32) -> Result<i128, StreamError> {
+    /// Add to the deposit balance of a flow stream. Only the sender can deposit.
+    /// Any outstanding debt is covered first and becomes claimable immediately.
+    pub fn deposit_flow(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        amount: i128,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.kind != StreamKind::Flow {
+            return Err(StreamError::InvalidStreamKind);
+        }
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if amount <= 0 {
+            return Err(StreamError::InvalidAmount);
+        }
+
+        let fee = Self::creation_fee(&stream, amount);
+        token::Client::new(&env, &stream.token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &(amount + fee),
+        );
+        Self::accrue_fee(&env, &stream.token, fee);
+
+        stream.total_amount += amount;
+        Self::refresh_debt(&env, &mut stream);
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("f_deposit"), sender.clone()),
+            (stream_id, amount),
+        );
+
+        Ok(())
+    }
+
+    /// Claim accrued tokens from an active stream.
+    /// The recipient can claim at any point — they receive tokens proportional to elapsed time.
+    pub fn claim(env: Env, recipient: Address, stream_id: u32) -> Result<i128, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.recipient != recipient {
+            return Err(StreamError::Unauthorized);
+        }
+
+        Self::pay_out(&env, stream_id, &mut stream, false)
+    }
+
+    /// Claim from the streams paying `recipient`, optionally only those in `token_filter`.
+    /// Makes one transfer per token and emits a single aggregated event. Streams that are
+    /// completed, cancelled or have nothing to claim are skipped. At most `MAX_PAGE_SIZE`
+    /// streams are visited per call, starting at `cursor`; pass the returned cursor to
+    /// claim the rest.
+    pub fn claim_all(
+        env: Env,
+        recipient: Address,
+        token_filter: Option<Address>,
+        cursor: u32,
+    ) -> Result<ClaimPage, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        let mut claims: Vec<StreamClaim> = Vec::new(&env);
+        let mut totals: Map<Address, i128> = Map::new(&env);
+        let (stream_ids, next_cursor) =
+            get_recipient_streams(&env, &recipient, cursor, MAX_PAGE_SIZE);
+
+        for stream_id in stream_ids.iter() {
+            let Some(mut stream) = get_stream(&env, stream_id) else {
+                continue;
+            };
+            if let Some(ref token) = token_filter {
+                if stream.token != *token {
+                    continue;
+                }
+            }
+            let Ok(amount) = Self::settle_claim(&env, &mut stream) else {
+                continue;
+            };
+
+            set_stream(&env, stream_id, &stream);
+            if stream.payout_token.is_some() {
+                // Streams paid in another token are swapped one by one
+                Self::pay_claim(&env, &stream, amount, false);
+            } else {
+                let primary = Self::deduct_payout(&env, &stream, amount, false);
+                let total = totals.get(stream.token.clone()).unwrap_or(0);
+                totals.set(stream.token.clone(), total + primary);
+            }
+            claims.push_back(StreamClaim {
+                stream_id,
+                token: stream.token,
+                amount,
+            });
+        }
+
+        let contract_addr = env.current_contract_address();
+        for (token, amount) in totals.iter() {
+            if amount > 0 {
+                token::Client::new(&env, &token).transfer(&contract_addr, &recipient, &amount);
+            }
+        }
+
+        env.events().publish(
+            (symbol_short!("claim_all"), recipient.clone()),
+            totals,
+        );
+
+        Ok(ClaimPage {
+            claims,
+            next_cursor,
+        })
+    }
+
+    /// Pay out a stream's accrued tokens on the recipient's behalf.
+    /// Anyone (e.g. a payroll bot) can trigger this; funds always go to the stream's
+    /// recipient, unless they have opted out of third-party payouts. Flat claim fees are
+    /// only charged when the recipient triggers the payout themselves.
+    pub fn withdraw_for(env: Env, caller: Address, stream_id: u32) -> Result<i128, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        caller.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        let pushed = caller != stream.recipient;
+        if pushed && get_push_opt_out(&env, &stream.recipient) {
+            return Err(StreamError::PushOptedOut);
+        }
+
+        Self::pay_out(&env, stream_id, &mut stream, pushed)
+    }
+
+    /// Pay out many streams at once, e.g. a whole department at month end.
+    /// Streams that do not exist, have nothing to pay, have ended, or whose recipient opted
+    /// out are skipped and reported as zero. Returns the amount paid per stream, in order.
+    pub fn withdraw_many(
+        env: Env,
+        caller: Address,
+        stream_ids: Vec<u32>,
+    ) -> Result<Vec<i128>, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        caller.require_auth();
+
+        let mut paid: Vec<i128> = Vec::new(&env);
+        for stream_id in stream_ids.iter() {
+            let Some(mut stream) = get_stream(&env, stream_id) else {
+                paid.push_back(0);
+                continue;
+            };
+
+            let pushed = caller != stream.recipient;
+            let amount = if pushed && get_push_opt_out(&env, &stream.recipient) {
+                0
+            } else {
+                Self::pay_out(&env, stream_id, &mut stream, pushed).unwrap_or(0)
+            };
+            paid.push_back(amount);
+        }
+
+        Ok(paid)
+    }
+
+    /// Opt in or out of payouts triggered by third parties via `withdraw_for`.
+    pub fn set_push_opt_out(
+        env: Env,
+        recipient: Address,
+        opt_out: bool,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        set_push_opt_out(&env, &recipient, opt_out);
+
+        env.events().publish(
+            (symbol_short!("push_opt"), recipient.clone()),
+            opt_out,
+        );
+
+        Ok(())
+    }
+
+    /// Cancel a stream. Only the sender (organization) or one of its operators can cancel.
+    /// Unclaimed tokens are returned to the sender. Already-claimed tokens stay with recipient.
+    /// Non-cancelable streams cannot be cancelled. For streams with a notice period the first
+    /// call only schedules the cancellation; accrual continues until the notice runs out, after
+    /// which calling again settles the stream.
+    pub fn cancel_stream(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if !Self::can_manage(&env, &stream, &sender) {
+            return Err(StreamError::Unauthorized);
+        }
+
+        let Some((claimable, refund)) = Self::settle_cancel(&env, &mut stream)? else {
+            set_stream(&env, stream_id, &stream);
+            env.events().publish(
+                (symbol_short!("c_notice"), sender.clone()),
+                (stream_id, stream.cancel_at),
+            );
+            return Ok(());
+        };
+
+        let contract_addr = env.current_contract_address();
+        let token_client = token::Client::new(&env, &stream.token);
+
+        if claimable > 0 {
+            Self::pay_recipient(&env, &stream, claimable);
+        }
+        if refund > 0 {
+            token_client.transfer(&contract_addr, &stream.sender, &refund);
+        }
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("cancel"), sender.clone()),
+            stream_id,
+        );
+
+        Ok(())
+    }
+
+    /// Cancel several streams at once, as their sender or one of its operators. Each
+    /// recipient is paid what they have accrued and the remainder is refunded to the
+    /// streams' sender with one transfer per token.
+    /// Streams with a notice period are scheduled for cancellation and left out of the report.
+    /// Fails as a whole if any stream cannot be cancelled.
+    pub fn cancel_batch(
+        env: Env,
+        sender: Address,
+        stream_ids: Vec<u32>,
+    ) -> Result<SettlementReport, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        Self::cancel_many(&env, &sender, stream_ids)
+    }
+
+    /// Cancel every live stream `sender` manages that pays `recipient`, e.g. when
+    /// offboarding.
+    /// Settles like `cancel_batch`, skipping streams whose policy does not allow
+    /// cancelling them right now. At most `MAX_PAGE_SIZE` of the recipient's streams are
+    /// visited per call, starting at `cursor`; the report's cursor resumes from there.
+    pub fn cancel_all_for_recipient(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        cursor: u32,
+    ) -> Result<SettlementReport, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let (candidates, next_cursor) =
+            get_recipient_streams(&env, &recipient, cursor, MAX_PAGE_SIZE);
+        let mut stream_ids: Vec<u32> = Vec::new(&env);
+        for stream_id in candidates.iter() {
+            if let Some(stream) = get_stream(&env, stream_id) {
+                if Self::can_manage(&env, &stream, &sender)
+                    && stream.status != StreamStatus::Cancelled
+                    && stream.status != StreamStatus::Completed
+                    && stream.status != StreamStatus::Held
+                    && Self::can_request_cancel(&env, &stream)
+                {
+                    stream_ids.push_back(stream_id);
+                }
+            }
+        }
+
+        let mut report = Self::cancel_many(&env, &sender, stream_ids)?;
+        report.next_cursor = next_cursor;
+        Ok(report)
+    }
+
+    /// Pause an active stream. Only the sender or one of its operators can pause.
+    /// Accrual is frozen until the stream is resumed; what accrued before the pause
+    /// remains claimable.
+    pub fn pause_stream(env: Env, sender: Address, stream_id: u32) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if !Self::can_manage(&env, &stream, &sender) {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status != StreamStatus::Active {
+            return Err(StreamError::StreamNotActive);
+        }
+        if stream.cancel_policy != CancelPolicy::Cancelable {
+            return Err(StreamError::NotCancelable);
+        }
+        if matches!(stream.kind, StreamKind::Milestone(..)) {
+            return Err(StreamError::InvalidStreamKind);
+        }
+
+        stream.status = StreamStatus::Paused;
+        stream.paused_at = env.ledger().timestamp();
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("pause"), sender.clone()),
+            stream_id,
+        );
+
+        Ok(())
+    }
+
+    /// Resume a paused stream. Only the sender or one of its operators can resume.
+    /// The end time is pushed back by the length of the pause so the full amount still streams.
+    pub fn resume_stream(env: Env, sender: Address, stream_id: u32) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if !Self::can_manage(&env, &stream, &sender) {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status != StreamStatus::Paused {
+            return Err(StreamError::StreamNotPaused);
+        }
+
+        let paused_for = env.ledger().timestamp() - stream.paused_at;
+        stream.paused_duration += paused_for;
+        stream.end_time = stream.end_time.saturating_add(paused_for);
+        stream.paused_at = 0;
+        stream.status = StreamStatus::Active;
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("resume"), sender.clone()),
+            (stream_id, paused_for),
+        );
+
+        Ok(())
+    }
+
+    /// Freeze a stream in dispute. Accrual stops and neither claims nor refunds are possible
+    /// until the stream's arbiter resolves the hold. Either the sender or the recipient can
+    /// place a hold, but only on a stream created with an arbiter.
+    pub fn hold_stream(env: Env, caller: Address, stream_id: u32) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        caller.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if caller != stream.sender && caller != stream.recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.arbiter.is_none() {
+            return Err(StreamError::InvalidArbiter);
+        }
+        if matches!(stream.kind, StreamKind::Milestone(..)) {
+            return Err(StreamError::InvalidStreamKind);
+        }
+        match stream.status {
+            StreamStatus::Active => stream.paused_at = env.ledger().timestamp(),
+            // A paused stream already stopped accruing at `paused_at`
+            StreamStatus::Paused => {}
+            StreamStatus::Held => return Err(StreamError::StreamOnHold),
+            _ => return Err(StreamError::StreamNotActive),
+        }
+        stream.status = StreamStatus::Held;
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("hold"), caller.clone()),
+            stream_id,
+        );
+
+        Ok(())
+    }
+
+    /// Resolve a dispute hold by splitting the stream's remaining escrow:
+    /// `recipient_amount` is paid to the recipient and the rest refunded to the sender,
+    /// together with any unspent renewal buffer. The stream ends as cancelled.
+    /// Only the stream's arbiter can resolve.
+    pub fn resolve_hold(
+        env: Env,
+        arbiter: Address,
+        stream_id: u32,
+        recipient_amount: i128,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        arbiter.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.arbiter.as_ref() != Some(&arbiter) {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status != StreamStatus::Held {
+            return Err(StreamError::StreamNotHeld);
+        }
+
+        let escrow = stream.total_amount - stream.claimed_amount;
+        if recipient_amount < 0 || recipient_amount > escrow {
+            return Err(StreamError::InvalidAmount);
+        }
+        let refund = escrow - recipient_amount + stream.renewal.buffer;
+
+        stream.claimed_amount += recipient_amount;
+        stream.renewal = Renewal::disabled();
+        stream.status = StreamStatus::Cancelled;
+
+        if recipient_amount > 0 {
+            Self::pay_recipient(&env, &stream, recipient_amount);
+        }
+        if refund > 0 {
+            token::Client::new(&env, &stream.token).transfer(
+                &env.current_contract_address(),
+                &stream.sender,
+                &refund,
+            );
+        }
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("resolve"), arbiter.clone()),
+            (stream_id, recipient_amount, refund),
+        );
+
+        Ok(())
+    }
+
+    /// Add escrow to a live stream. Only the sender can top up.
+    /// If `new_end_time` is later than the current end time the stream is extended and the
+    /// added funds stream over the longer period; if it equals the current end time the
+    /// funds are added without extending, raising the rate. Amounts already accrued are
+    /// never changed.
+    pub fn top_up_stream(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        amount: i128,
+        new_end_time: u64,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if stream.status == StreamStatus::Completed {
+            return Err(StreamError::StreamCompleted);
+        }
+        if stream.status == StreamStatus::Held {
+            return Err(StreamError::StreamOnHold);
+        }
+        if stream.kind != StreamKind::Linear {
+            return Err(StreamError::InvalidStreamKind);
+        }
+        if amount <= 0 {
+            return Err(StreamError::InvalidAmount);
+        }
+        if new_end_time < stream.end_time {
+            return Err(StreamError::InvalidDuration);
+        }
+
+        // Fix everything accrued so far, then stream the rest evenly from here
+        let split_time = Self::stream_time(&env, &stream).max(stream.start_time);
+        let end_time = new_end_time - stream.paused_duration;
+        if end_time <= split_time {
+            return Err(StreamError::InvalidDuration);
+        }
+
+        let fee = Self::creation_fee(&stream, amount);
+        token::Client::new(&env, &stream.token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &(amount + fee),
+        );
+        Self::accrue_fee(&env, &stream.token, fee);
+
+        let unstreamed = Self::split_segments(&mut stream, split_time);
+        let segment_amount = unstreamed + amount;
+        stream.segments.push_back(StreamSegment {
+            start_time: split_time,
+            end_time,
+            amount: segment_amount,
+        });
+        stream.total_amount += amount;
+        stream.end_time = new_end_time;
+        stream.rate_per_second = segment_amount / ((end_time - split_time) as i128);
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("top_up"), sender.clone()),
+            (stream_id, amount, new_end_time),
+        );
+
+        Ok(())
+    }
+
+    /// Change the stream's rate from `effective_at` until its end time. Only the sender can
+    /// change rates, and only from now onwards so past accruals are never affected.
+    /// The difference in escrow is pulled from, or refunded to, the sender. Rate cuts are
+    /// refused while the recipient has an unrepaid advance, on non-cancelable streams and
+    /// during a notice period.
+    pub fn update_rate(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        new_rate_per_second: i128,
+        effective_at: u64,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status != StreamStatus::Active {
+            return Err(StreamError::StreamNotActive);
+        }
+        if stream.kind != StreamKind::Linear {
+            return Err(StreamError::InvalidStreamKind);
+        }
+        if new_rate_per_second <= 0 {
+            return Err(StreamError::InvalidAmount);
+        }
+        if effective_at < env.ledger().timestamp() {
+            return Err(StreamError::InvalidStartTime);
+        }
+        if effective_at >= stream.end_time {
+            return Err(StreamError::InvalidDuration);
+        }
+
+        let split_time = (effective_at - stream.paused_duration).max(stream.start_time);
+        let end_time = stream.end_time - stream.paused_duration;
+
+        let unstreamed = Self::split_segments(&mut stream, split_time);
+        let segment_amount = new_rate_per_second * ((end_time - split_time) as i128);
+        if segment_amount < unstreamed {
+            // A rate cut takes back escrow, so it is bound by the cancel policy, and the
+            // refund must not include advances already paid out
+            Self::require_reducible(&stream)?;
+            Self::require_no_advance(&env, &mut stream)?;
+        }
+        stream.segments.push_back(StreamSegment {
+            start_time: split_time,
+            end_time,
+            amount: segment_amount,
+        });
+        stream.total_amount += segment_amount - unstreamed;
+        stream.rate_per_second = new_rate_per_second;
+        if stream.total_amount < stream.claimed_amount {
+            return Err(StreamError::AdvanceOutstanding);
+        }
+
+        let token_client = token::Client::new(&env, &stream.token);
+        let contract_addr = env.current_contract_address();
+        if segment_amount > unstreamed {
+            let added = segment_amount - unstreamed;
+            let fee = Self::creation_fee(&stream, added);
+            token_client.transfer(&sender, &contract_addr, &(added + fee));
+            Self::accrue_fee(&env, &stream.token, fee);
+        } else if unstreamed > segment_amount {
+            token_client.transfer(&contract_addr, &sender, &(unstreamed - segment_amount));
+        }
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("rate_upd"), sender.clone()),
+            (stream_id, new_rate_per_second, effective_at),
+        );
+
+        Ok(())
+    }
+
+    /// Move the claim rights of a stream to a new recipient address.
+    /// Only the current recipient can transfer, and only if the sender has not locked it.
+    /// Anything accrued but unclaimed moves with the stream.
+    pub fn transfer_stream(
+        env: Env,
+        recipient: Address,
+        stream_id: u32,
+        new_recipient: Address,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.recipient != recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        if !stream.transferable {
+            return Err(StreamError::StreamNotTransferable);
+        }
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if stream.status == StreamStatus::Completed {
+            return Err(StreamError::StreamCompleted);
+        }
+        if stream.status == StreamStatus::Held {
+            return Err(StreamError::StreamOnHold);
+        }
+        if new_recipient == recipient || new_recipient == stream.sender {
+            return Err(StreamError::InvalidRecipient);
+        }
+        // The arbiter must never become a party to the disputes it resolves
+        if stream.arbiter.as_ref() == Some(&new_recipient) {
+            return Err(StreamError::InvalidArbiter);
+        }
+
+        // Splits and payout preferences belong to the old recipient;
+        // sender-mandated withholding stays
+        stream.recipient = new_recipient.clone();
+        stream.splits = Vec::new(&env);
+        stream.payout_token = None;
+        set_stream(&env, stream_id, &stream);
+        remove_recipient_stream(&env, &recipient, stream_id);
+        add_recipient_stream(&env, &new_recipient, stream_id);
+
+        env.events().publish(
+            (symbol_short!("transfer"), recipient.clone()),
+            (stream_id, new_recipient),
+        );
+
+        Ok(())
+    }
+
+    /// Route shares of each payout to other addresses, e.g. savings or a spouse.
+    /// Only the recipient can set their splits, at most `MAX_SPLITS` of them. Each share is
+    /// in basis points of the payout; whatever is left, including rounding, goes to the
+    /// recipient. Replaces earlier splits.
+    pub fn set_splits(
+        env: Env,
+        recipient: Address,
+        stream_id: u32,
+        splits: Vec<PayoutSplit>,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.recipient != recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        Self::validate_splits(&splits, &stream.withholding)?;
+
+        stream.splits = splits;
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("splits"), recipient.clone()),
+            stream_id,
+        );
+
+        Ok(())
+    }
+
+    /// Choose a different token to be paid in. Each payout is swapped through the configured
+    /// router, accepting no less than `min_rate` of `payout_token` per unit of the stream
+    /// token (scaled by 10^7), up to `MAX_PAYOUT_RATE`. If the swap fails the payout is made
+    /// in the stream token. Only claims are swapped; payouts when the stream is settled by
+    /// someone else, such as on cancellation, are always made in the stream token.
+    /// Pass `None` to be paid in the stream token again. Only the recipient can set this.
+    pub fn set_payout_token(
+        env: Env,
+        recipient: Address,
+        stream_id: u32,
+        payout_token: Option<Address>,
+        min_rate: i128,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.recipient != recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        if !(0..=MAX_PAYOUT_RATE).contains(&min_rate) {
+            return Err(StreamError::InvalidAmount);
+        }
+
+        stream.payout_token = payout_token.filter(|token| *token != stream.token);
+        stream.payout_min_rate = min_rate;
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("payout"), recipient.clone()),
+            (stream_id, stream.payout_token.clone()),
+        );
+
+        Ok(())
+    }
+
+    /// Set or clear a mandated withholding share (e.g. tax) on every payout of a stream.
+    /// Only the sender can set withholding; the recipient cannot override it. The share is
+    /// capped at `MAX_WITHHOLDING_BPS` and cannot be paid to the sender. Pay accrued so far
+    /// is paid out under the old terms first, so the change only affects later accruals.
+    /// If the recipient has opted out of third-party payouts nothing is paid out: a lower
+    /// share then also applies to pay already accrued, and a higher one is refused until
+    /// they have claimed it. Raising the share cuts the recipient's pay, so it is refused
+    /// on non-cancelable streams and during a notice period; lowering or clearing it is
+    /// always allowed.
+    pub fn set_withholding(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        withholding: Option<PayoutSplit>,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        let current_bps = stream.withholding.first().map_or(0, |split| split.bps);
+        let raised = withholding.as_ref().is_some_and(|split| split.bps > current_bps);
+        if raised {
+            Self::require_reducible(&stream)?;
+        }
+        let mut withholding_vec = Vec::new(&env);
+        if let Some(split) = withholding {
+            if split.bps > MAX_WITHHOLDING_BPS || split.destination == stream.sender {
+                return Err(StreamError::InvalidSplits);
+            }
+            withholding_vec.push_back(split);
+        }
+        Self::validate_splits(&stream.splits, &withholding_vec)?;
+
+        if get_push_opt_out(&env, &stream.recipient) {
+            if raised && Self::calculate_claimable(&env, &stream) > 0 {
+                return Err(StreamError::PushOptedOut);
+            }
+        } else {
+            match Self::settle_claim(&env, &mut stream) {
+                Ok(accrued) => Self::pay_recipient_as(&env, &stream, accrued, true),
+                Err(StreamError::NothingToClaim) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        stream.withholding = withholding_vec;
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("withhold"), sender.clone()),
+            stream_id,
+        );
+
+        Ok(())
+    }
+
+    /// Lock or unlock recipient transfers for a stream. Only the sender can change this.
+    pub fn set_transferable(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        transferable: bool,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+
+        stream.transferable = transferable;
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("t_lock"), sender.clone()),
+            (stream_id, transferable),
+        );
+
+        Ok(())
+    }
+
+    /// Set the most a recipient may hold in unrepaid advances, in basis points of the
+    /// stream's unstreamed remainder. Zero disables advances. Only the sender can set this.
+    pub fn set_advance_limit(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        limit_bps: u32,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        if limit_bps > MAX_BPS {
+            return Err(StreamError::InvalidAmount);
+        }
+
+        stream.advance_limit_bps = limit_bps;
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("adv_limit"), sender.clone()),
+            (stream_id, limit_bps),
+        );
+
+        Ok(())
+    }
+
+    /// Pay the recipient `amount` of wages that have not accrued yet. The advance comes out
+    /// of the sender's escrow and is repaid by later accruals before anything more becomes
+    /// claimable. Outstanding advances are capped by the stream's advance limit.
+    pub fn request_advance(
+        env: Env,
+        recipient: Address,
+        stream_id: u32,
+        amount: i128,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        recipient.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.recipient != recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status != StreamStatus::Active {
+            return Err(StreamError::StreamNotActive);
+        }
+        if stream.kind == StreamKind::Flow || matches!(stream.kind, StreamKind::Milestone(..)) {
+            return Err(StreamError::InvalidStreamKind);
+        }
+        if amount <= 0 {
+            return Err(StreamError::InvalidAmount);
+        }
+
+        let accrued = Self::calculate_accrued(&env, &stream);
+        let unstreamed = stream.total_amount - accrued;
+        let limit = unstreamed * (stream.advance_limit_bps as i128) / (MAX_BPS as i128);
+        if stream.claimed_amount + amount - accrued > limit {
+            return Err(StreamError::AdvanceLimitExceeded);
+        }
+
+        stream.claimed_amount += amount;
+        Self::refresh_advance(&env, &mut stream);
+        Self::pay_claim(&env, &stream, amount, false);
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("advance"), recipient),
+            (stream_id, amount),
+        );
+
+        Ok(())
+    }
+
+    /// Turn automatic renewal on or off. While on, each time the stream ends a new period
+    /// as long as the stream and paying its total amount, as they stood when renewal was
+    /// first turned on, is appended.
+    /// Renewals are funded from the sender's token allowance to this contract or from a
+    /// deposit buffer. Turning renewal off refunds the buffer. Only the sender can set this.
+    pub fn set_renewal(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        funding: RenewalFunding,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if stream.status == StreamStatus::Completed {
+            return Err(StreamError::StreamCompleted);
+        }
+        if stream.kind != StreamKind::Linear {
+            return Err(StreamError::InvalidStreamKind);
+        }
+
+        if funding == RenewalFunding::None {
+            let buffer = stream.renewal.buffer;
+            stream.renewal = Renewal::disabled();
+            if buffer > 0 {
+                token::Client::new(&env, &stream.token).transfer(
+                    &env.current_contract_address(),
+                    &sender,
+                    &buffer,
+                );
+            }
+        } else {
+            // Switching funding keeps the period terms fixed when renewal was first enabled
+            if stream.renewal.funding == RenewalFunding::None {
+                stream.renewal.period_length =
+                    stream.end_time - stream.start_time - stream.paused_duration;
+                stream.renewal.period_amount = stream.total_amount;
+            }
+            stream.renewal.funding = funding.clone();
+            stream.renewal.paused = false;
+        }
+
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("renewal"), sender.clone()),
+            (stream_id, funding),
+        );
+
+        Ok(())
+    }
+
+    /// Add to the deposit buffer that funds renewals, and resume renewal if it was paused
+    /// for lack of funds. Only the sender can deposit.
+    pub fn deposit_renewal(
+        env: Env,
+        sender: Address,
+        stream_id: u32,
+        amount: i128,
+    ) -> Result<(), StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+        sender.require_auth();
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.sender != sender {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.renewal.funding == RenewalFunding::None {
+            return Err(StreamError::RenewalInactive);
+        }
+        if amount <= 0 {
+            return Err(StreamError::InvalidAmount);
+        }
+
+        token::Client::new(&env, &stream.token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        stream.renewal.buffer += amount;
+        stream.renewal.paused = false;
+        set_stream(&env, stream_id, &stream);
+
+        env.events().publish(
+            (symbol_short!("rn_fund"), sender.clone()),
+            (stream_id, amount),
+        );
+
+        Ok(())
+    }
+
+    /// Start every renewal period that is due on a stream with renewal turned on.
+    /// Anyone (e.g. a payroll bot) can trigger this. If a period cannot be funded, renewal
+    /// is paused and a `rn_paused` event is emitted; the sender resumes it by funding
+    /// again. Each period's escrow is charged the creation fee like any other deposit.
+    /// Returns the number of periods started.
+    pub fn renew_stream(env: Env, stream_id: u32) -> Result<u32, StreamError> {
+        if !has_admin(&env) {
+            return Err(StreamError::NotInitialized);
+        }
+
+        let mut stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if stream.status != StreamStatus::Active {
+            return Err(StreamError::StreamNotActive);
+        }
+        if stream.renewal.funding == RenewalFunding::None
+            || stream.renewal.paused
+            || stream.cancel_at != 0
+        {
+            return Err(StreamError::RenewalInactive);
+        }
+
+        let contract_addr = env.current_contract_address();
+        let token_client = token::Client::new(&env, &stream.token);
+        let amount = stream.renewal.period_amount;
+        let fee = Self::creation_fee(&stream, amount);
+        let now = Self::stream_time(&env, &stream);
+        let mut renewed: u32 = 0;
+
+        loop {
+            let period_start = stream.end_time - stream.paused_duration;
+            if now < period_start {
+                break;
+            }
+
+            let funded = match stream.renewal.funding {
+                RenewalFunding::Allowance => {
+                    let available = token_client
+                        .allowance(&stream.sender, &contract_addr)
+                        .min(token_client.balance(&stream.sender))
+                        >= amount + fee;
+                    if available {
+                        token_client.transfer_from(
+                            &contract_addr,
+                            &stream.sender,
+                            &contract_addr,
+                            &(amount + fee),
+                        );
+                    }
+                    available
+                }
+                RenewalFunding::Buffer => {
+                    let available = stream.renewal.buffer >= amount + fee;
+                    if available {
+                        stream.renewal.buffer -= amount + fee;
+                    }
+                    available
+                }
+                RenewalFunding::None => false,
+            };
+            if !funded {
+                stream.renewal.paused = true;
+                env.events().publish(
+                    (symbol_short!("rn_paused"), stream.sender.clone()),
+                    (stream_id, period_start),
+                );
+                break;
+            }
+            Self::accrue_fee(&env, &stream.token, fee);
+
+            let period_end = period_start + stream.renewal.period_length;
+            stream.segments.push_back(StreamSegment {
+                start_time: period_start,
+                end_time: period_end,
+                amount,
+            });
+            stream.total_amount += amount;
+            stream.end_time = stream.end_time.saturating_add(stream.renewal.period_length);
+            renewed += 1;
+
+            env.events().publish(
+                (symbol_short!("renew"), stream.sender.clone()),
+                (stream_id, period_end),
+            );
+        }
+
+        if renewed == 0 && !stream.renewal.paused {
+            return Err(StreamError::RenewalNotDue);
+        }
+
+        set_stream(&env, stream_id, &stream);
+
+        Ok(renewed)
+    }
+
+    /// Extend the storage lifetime of a stream so it is not archived while in flight.
+    /// Anyone may call this, e.g. a keeper for long-running streams.
+    pub fn bump_stream(env: Env, stream_id: u32) -> Result<(), StreamError> {
+        if get_stream(&env, stream_id).is_none() {
+            return Err(StreamError::StreamNotFound);
+        }
+        extend_stream_ttl(&env, stream_id);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Archive a cancelled or completed stream, freeing its storage. Only a hash of the
+    /// record is kept; the full record is emitted in the event so it can be restored.
+    /// Only the sender or the recipient can archive, since they are the ones who need to
+    /// keep the record.
+    pub fn archive_stream(env: Env, caller: Address, stream_id: u32) -> Result<(), StreamError> {
+        caller.require_auth();
+
+        let stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+
+        if caller != stream.sender && caller != stream.recipient {
+            return Err(StreamError::Unauthorized);
+        }
+        if stream.status != StreamStatus::Cancelled && stream.status != StreamStatus::Completed {
+            return Err(StreamError::StreamStillLive);
+        }
+
+        set_archived_stream(&env, stream_id, &Self::record_hash(&env, &stream));
+        remove_stream(&env, stream_id);
+
+        env.events().publish(
+            (symbol_short!("archive"), stream_id),
+            stream,
+        );
+
+        Ok(())
+    }
+
+    /// Restore an archived stream from its record, as emitted when it was archived.
+    /// Anyone may call this; the record must match the archived hash exactly.
+    pub fn restore_stream(env: Env, stream: PayrollStream) -> Result<(), StreamError> {
+        let hash = get_archived_stream(&env, stream.id)
+            .ok_or(StreamError::StreamNotFound)?;
+        if Self::record_hash(&env, &stream) != hash {
+            return Err(StreamError::InvalidArchiveRecord);
+        }
+
+        remove_archived_stream(&env, stream.id);
+        set_stream(&env, stream.id, &stream);
+
+        env.events().publish(
+            (symbol_short!("restore"), stream.id),
+            (),
+        );
+
+        Ok(())
+    }
+
+    // ── Internal Helpers ─────────────────────────────────────────
+
+    /// Release a milestone to the recipient or refund it to the sender. Only pending or
+    /// disputed milestones can be settled; the stream completes once none remain open.
+    fn resolve_milestone(
+        env: &Env,
+        stream_id: u32,
+        stream: &mut PayrollStream,
+        index: u32,
+        release: bool,
+    ) -> Result<(), StreamError> {
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if stream.status == StreamStatus::Completed {
+            return Err(StreamError::StreamCompleted);
+        }
+        let StreamKind::Milestone(_, ref mut milestones) = stream.kind else {
+            return Err(StreamError::InvalidStreamKind);
+        };
+
+        let mut milestone = milestones.get(index)
+            .ok_or(StreamError::MilestoneNotFound)?;
+        if milestone.status != MilestoneStatus::Pending
+            && milestone.status != MilestoneStatus::Disputed
+        {
+            return Err(StreamError::InvalidMilestoneState);
+        }
+        milestone.status = if release {
+            MilestoneStatus::Released
+        } else {
+            MilestoneStatus::Refunded
+        };
+        milestones.set(index, milestone.clone());
+        let open = milestones.iter().any(|m| {
+            m.status == MilestoneStatus::Pending || m.status == MilestoneStatus::Disputed
+        });
+
+        if release {
+            stream.claimed_amount += milestone.amount;
+            Self::pay_recipient(env, stream, milestone.amount);
+        } else {
+            // Refunded milestones leave escrow, so they no longer count towards the total
+            stream.total_amount -= milestone.amount;
+            token::Client::new(env, &stream.token).transfer(
+                &env.current_contract_address(),
+                &stream.sender,
+                &milestone.amount,
+            );
+        }
+        if !open {
+            stream.status = StreamStatus::Completed;
+        }
+
+        set_stream(env, stream_id, stream);
+
+        let topic = if release {
+            symbol_short!("m_release")
+        } else {
+            symbol_short!("m_refund")
+        };
+        env.events().publish((topic, stream_id), (index, milestone.amount));
+
+        Ok(())
+    }
+
+    /// Whether an open milestone's review, and for a disputed one the dispute fallback,
+    /// has run out.
+    fn milestone_timed_out(env: &Env, terms: &MilestoneTerms, milestone: &Milestone) -> bool {
+        let mut deadline = milestone.due_date.saturating_add(terms.review_period);
+        match milestone.status {
+            MilestoneStatus::Pending => {}
+            MilestoneStatus::Disputed => deadline = deadline.saturating_add(DISPUTE_TIMEOUT),
+            _ => return false,
+        }
+        env.ledger().timestamp() >= deadline
+    }
+
+    /// Whether an open milestone has timed out under an auto-release policy.
+    fn milestone_releasable(env: &Env, terms: &MilestoneTerms, milestone: &Milestone) -> bool {
+        terms.on_timeout == MilestoneTimeout::AutoRelease
+            && Self::milestone_timed_out(env, terms, milestone)
+    }
+
+    /// Close the open milestones of a cancelled stream: those that timed out under an
+    /// auto-release policy are released, the rest refunded.
+    fn close_milestones(env: &Env, terms: &MilestoneTerms, milestones: &mut Vec<Milestone>) {
+        for index in 0..milestones.len() {
+            let mut milestone = milestones.get_unchecked(index);
+            if milestone.status != MilestoneStatus::Pending
+                && milestone.status != MilestoneStatus::Disputed
+            {
+                continue;
+            }
+            milestone.status = if Self::milestone_releasable(env, terms, &milestone) {
+                MilestoneStatus::Released
+            } else {
+                MilestoneStatus::Refunded
+            };
+            milestones.set(index, milestone);
+        }
+    }
+
+    /// Record how much of the recipient's payouts is still an unearned advance.
+    fn refresh_advance(env: &Env, stream: &mut PayrollStream) {
+        let accrued = Self::calculate_accrued(env, stream);
+        stream.advance_outstanding = (stream.claimed_amount - accrued).max(0);
+    }
+
+    /// Record how much a flow stream has accrued beyond its deposits.
+    fn refresh_debt(env: &Env, stream: &mut PayrollStream) {
+        if stream.kind == StreamKind::Flow {
+            stream.debt = (Self::calculate_owed(env, stream) - stream.total_amount).max(0);
+        }
+    }
+
+    /// Refuse changes that cut the recipient's pay when the cancel policy would not allow
+    /// stopping the stream right now.
+    fn require_reducible(stream: &PayrollStream) -> Result<(), StreamError> {
+        if stream.cancel_policy == CancelPolicy::NonCancelable {
+            return Err(StreamError::NotCancelable);
+        }
+        if stream.cancel_at != 0 {
+            return Err(StreamError::NoticePeriodActive);
+        }
+        Ok(())
+    }
+
+    /// Refuse escrow changes while part of the recipient's payouts is an unearned advance.
+    fn require_no_advance(env: &Env, stream: &mut PayrollStream) -> Result<(), StreamError> {
+        Self::refresh_advance(env, stream);
+        if stream.advance_outstanding > 0 {
+            return Err(StreamError::AdvanceOutstanding);
+        }
+        Ok(())
+    }
+
+    /// Hash identifying a stream record, used to verify restores.
+    fn record_hash(env: &Env, stream: &PayrollStream) -> BytesN<32> {
+        env.crypto().sha256(&stream.clone().to_xdr(env)).into()
+    }
+
+    /// Mark a stream cancelled and split its escrow, without moving tokens.
+    /// Returns `(owed to recipient, refund to sender)`, or `None` if the stream has a notice
+    /// period and the cancellation was only scheduled.
+    fn settle_cancel(
+        env: &Env,
+        stream: &mut PayrollStream,
+    ) -> Result<Option<(i128, i128)>, StreamError> {
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if stream.status == StreamStatus::Completed {
+            return Err(StreamError::StreamCompleted);
+        }
+        if stream.status == StreamStatus::Held {
+            return Err(StreamError::StreamOnHold);
+        }
+
+        match stream.cancel_policy {
+            CancelPolicy::Cancelable => {}
+            CancelPolicy::NonCancelable => return Err(StreamError::NotCancelable),
+            CancelPolicy::Notice(notice) => {
+                if stream.cancel_at == 0 {
+                    stream.cancel_at = env.ledger().timestamp().saturating_add(notice);
+                    return Ok(None);
+                }
+                if env.ledger().timestamp() < stream.cancel_at {
+                    return Err(StreamError::NoticePeriodActive);
+                }
+            }
+        }
+
+        // Calculate what recipient is owed up to now. Advances beyond what was earned
+        // stay with the recipient and are recorded as unrepaid debt.
+        let accrued = Self::calculate_cancel_accrued(env, stream);
+        let claimable = (accrued - stream.claimed_amount).max(0);
+        // Any unspent renewal buffer goes back to the sender with the refund.
+        let refund =
+            stream.total_amount - stream.claimed_amount - claimable + stream.renewal.buffer;
+
+        stream.claimed_amount += claimable;
+        Self::refresh_debt(env, stream);
+        stream.renewal = Renewal::disabled();
+        if let StreamKind::Milestone(ref terms, ref mut milestones) = stream.kind {
+            Self::close_milestones(env, terms, milestones);
+        }
+        stream.advance_outstanding = (stream.claimed_amount - accrued).max(0);
+        stream.status = StreamStatus::Cancelled;
+
+        Ok(Some((claimable, refund)))
+    }
+
+    /// Summarise one page of a stream index from `cursor`, examining at most `limit`
+    /// entries.
+    fn list_streams(
+        env: &Env,
+        index: &StreamIndex,
+        cursor: u32,
+        limit: u32,
+        status: Option<StreamStatus>,
+        token: Option<Address>,
+    ) -> StreamPage {
+        let (ids, next_cursor) = get_index_page(env, index, cursor, limit.min(MAX_PAGE_SIZE));
+        let mut streams: Vec<StreamSummary> = Vec::new(env);
+
+        for stream_id in ids.iter() {
+            let Some(stream) = get_stream(env, stream_id) else {
+                continue;
+            };
+            if status.as_ref().is_some_and(|s| *s != stream.status)
+                || token.as_ref().is_some_and(|t| *t != stream.token)
+            {
+                continue;
+            }
+
+            let claimable = if stream.status == StreamStatus::Cancelled {
+                0
+            } else {
+                Self::calculate_claimable(env, &stream)
+            };
+            streams.push_back(StreamSummary {
+                id: stream.id,
+                sender: stream.sender,
+                recipient: stream.recipient,
+                token: stream.token,
+                total_amount: stream.total_amount,
+                claimed_amount: stream.claimed_amount,
+                claimable,
+                status: stream.status,
+            });
+        }
+
+        StreamPage {
+            streams,
+            next_cursor,
+        }
+    }
+
+    /// Check that `admin` is the stored admin and has authorized the call.
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), StreamError> {
+        if !has_admin(env) {
+            return Err(StreamError::NotInitialized);
+        }
+        admin.require_auth();
+        if get_admin(env) != *admin {
+            return Err(StreamError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Whether `caller` may pause, resume or cancel `stream`: its sender, or an active
+    /// operator when the stream belongs to the org.
+    fn can_manage(env: &Env, stream: &PayrollStream, caller: &Address) -> bool {
+        stream.sender == *caller
+            || (is_operator(env, caller) && stream.sender == get_admin(env))
+    }
+
+    /// Whether the sender could cancel, or schedule the cancellation of, a stream right now.
+    fn can_request_cancel(env: &Env, stream: &PayrollStream) -> bool {
+        match stream.cancel_policy {
+            CancelPolicy::Cancelable => true,
+            CancelPolicy::NonCancelable => false,
+            CancelPolicy::Notice(_) => {
+                stream.cancel_at == 0 || env.ledger().timestamp() >= stream.cancel_at
+            }
+        }
+    }
+
+    /// Cancel `stream_ids` on behalf of `sender`, the streams' sender or one of its
+    /// operators, paying recipients per stream and aggregating refunds per token.
+    /// Refunds always go to each stream's own sender.
+    fn cancel_many(
+        env: &Env,
+        sender: &Address,
+        stream_ids: Vec<u32>,
+    ) -> Result<SettlementReport, StreamError> {
+        let contract_addr = env.current_contract_address();
+        let mut settlements: Vec<StreamSettlement> = Vec::new(env);
+        let mut refunds: Map<Address, i128> = Map::new(env);
+        let mut owed: Map<(Address, Address), i128> = Map::new(env);
+
+        for stream_id in stream_ids.iter() {
+            let mut stream = get_stream(env, stream_id)
+                .ok_or(StreamError::StreamNotFound)?;
+            if !Self::can_manage(env, &stream, sender) {
+                return Err(StreamError::Unauthorized);
+            }
+
+            let Some((paid, refunded)) = Self::settle_cancel(env, &mut stream)? else {
+                set_stream(env, stream_id, &stream);
+                env.events().publish(
+                    (symbol_short!("c_notice"), sender.clone()),
+                    (stream_id, stream.cancel_at),
+                );
+                continue;
+            };
+            if paid > 0 {
+                Self::pay_recipient(env, &stream, paid);
+            }
+            let total = refunds.get(stream.token.clone()).unwrap_or(0);
+            refunds.set(stream.token.clone(), total + refunded);
+            let key = (stream.sender.clone(), stream.token.clone());
+            owed.set(key.clone(), owed.get(key).unwrap_or(0) + refunded);
+
+            set_stream(env, stream_id, &stream);
+            settlements.push_back(StreamSettlement {
+                stream_id,
+                recipient: stream.recipient,
+                token: stream.token,
+                paid,
+                refunded,
+            });
+        }
+
+        for ((owner, token), amount) in owed.iter() {
+            if amount > 0 {
+                token::Client::new(env, &token).transfer(&contract_addr, &owner, &amount);
+            }
+        }
+
+        env.events().publish(
+            (symbol_short!("b_cancel"), sender.clone()),
+            stream_ids,
+        );
+
+        Ok(SettlementReport {
+            settlements,
+            refunds,
+            next_cursor: None,
+        })
+    }
+
+    /// Record a claim of everything currently claimable, without moving tokens.
+    /// Returns the amount the recipient is now owed a transfer of.
+    fn settle_claim(env: &Env, stream: &mut PayrollStream) -> Result<i128, StreamError> {
+        if stream.status == StreamStatus::Cancelled {
+            return Err(StreamError::StreamAlreadyCancelled);
+        }
+        if stream.status == StreamStatus::Completed {
+            return Err(StreamError::StreamCompleted);
+        }
+        if stream.status == StreamStatus::Held {
+            return Err(StreamError::StreamOnHold);
+        }
+
+        let claimable = Self::calculate_claimable(env, stream);
+        if claimable <= 0 {
+            return Err(StreamError::NothingToClaim);
+        }
+
+        stream.claimed_amount += claimable;
+        stream.last_claim_time = env.ledger().timestamp();
+        Self::refresh_advance(env, stream);
+        Self::refresh_debt(env, stream);
+
+        // Check if stream is fully claimed. Flow streams and renewing streams stay open
+        // for further periods.
+        if stream.kind != StreamKind::Flow
+            && stream.renewal.funding == RenewalFunding::None
+            && stream.claimed_amount >= stream.total_amount
+        {
+            stream.status = StreamStatus::Completed;
+        }
+
+        Ok(claimable)
+    }
+
+    /// Size of a fee of `mode` charged on `amount`, never more than `amount` itself.
+    fn fee_amount(mode: &FeeMode, amount: i128) -> i128 {
+        let fee = match mode {
+            FeeMode::None => 0,
+            FeeMode::Flat(flat) => *flat,
+            FeeMode::Proportional(bps) => amount * (*bps as i128) / (MAX_BPS as i128),
+        };
+        fee.min(amount)
+    }
+
+    /// Fee the sender pays on top of `amount` of escrow when creating or adding to a stream.
+    fn creation_fee(stream: &PayrollStream, amount: i128) -> i128 {
+        Self::fee_amount(&stream.creation_fee, amount)
+    }
+
+    /// Fee terms a new stream charges on added escrow under the current configuration.
+    fn creation_fee_terms(env: &Env) -> FeeMode {
+        match get_fee_config(env) {
+            Some(config) if config.timing == FeeTiming::OnCreate => config.mode,
+            _ => FeeMode::None,
+        }
+    }
+
+    /// Fee terms a new stream deducts from each payout under the current configuration.
+    fn claim_fee_terms(env: &Env) -> FeeMode {
+        match get_fee_config(env) {
+            Some(config) if config.timing == FeeTiming::OnClaim => config.mode,
+            _ => FeeMode::None,
+        }
+    }
+
+    /// Add a collected fee to the withdrawable balance for `token`.
+    fn accrue_fee(env: &Env, token: &Address, fee: i128) {
+        if fee > 0 {
+            set_accrued_fees(env, token, get_accrued_fees(env, token) + fee);
+        }
+    }
+
+    /// Deduct the stream's claim fee from a payout and return what is left to pay out.
+    /// Flat fees are waived on `pushed` payouts triggered by a third party, who could
+    /// otherwise split the recipient's pay into many fee-bearing payouts.
+    fn take_claim_fee(env: &Env, stream: &PayrollStream, amount: i128, pushed: bool) -> i128 {
+        let fee = match stream.claim_fee {
+            FeeMode::Flat(_) if pushed => 0,
+            ref mode => Self::fee_amount(mode, amount),
+        };
+        Self::accrue_fee(env, &stream.token, fee);
+        amount - fee
+    }
+
+    /// Append a payout to the stream's claim history.
+    fn record_claim(env: &Env, stream: &PayrollStream, amount: i128) {
+        add_claim_record(
+            env,
+            stream.id,
+            &ClaimRecord {
+                amount,
+                timestamp: env.ledger().timestamp(),
+                destination: stream.recipient.clone(),
+            },
+        );
+    }
+
+    /// Transfer each payout split's share of `amount` and return what is left for the
+    /// primary recipient, including any rounding remainder.
+    fn pay_splits(env: &Env, stream: &PayrollStream, amount: i128) -> i128 {
+        let token_client = token::Client::new(env, &stream.token);
+        let contract_addr = env.current_contract_address();
+
+        let mut splits = stream.splits.clone();
+        splits.append(&stream.withholding);
+
+        let mut remainder = amount;
+        for split in splits.iter() {
+            let share = amount * (split.bps as i128) / (MAX_BPS as i128);
+            if share > 0 {
+                token_client.transfer(&contract_addr, &split.destination, &share);
+                remainder -= share;
+            }
+        }
+        remainder
+    }
+
+    /// Record a payout of `amount`, deduct the claim fee and pay the splits. Returns what
+    /// is left for the recipient, who has not been paid yet.
+    fn deduct_payout(env: &Env, stream: &PayrollStream, amount: i128, pushed: bool) -> i128 {
+        Self::record_claim(env, stream, amount);
+        let net = Self::take_claim_fee(env, stream, amount, pushed);
+        Self::pay_splits(env, stream, net)
+    }
+
+    /// Pay `amount` out of escrow to the stream's recipient in the stream token, honouring
+    /// payout splits. Used when the sender, an operator, the reviewer or the arbiter
+    /// settles the stream, so the recipient's payout token can never block settlement.
+    fn pay_recipient(env: &Env, stream: &PayrollStream, amount: i128) {
+        Self::pay_recipient_as(env, stream, amount, false);
+    }
+
+    /// Like `pay_recipient`, for a payout that may have been `pushed` by a third party.
+    fn pay_recipient_as(env: &Env, stream: &PayrollStream, amount: i128, pushed: bool) {
+        let remainder = Self::deduct_payout(env, stream, amount, pushed);
+        if remainder > 0 {
+            token::Client::new(env, &stream.token).transfer(
+                &env.current_contract_address(),
+                &stream.recipient,
+                &remainder,
+            );
+        }
+    }
+
+    /// Pay a claim of `amount` to the stream's recipient, honouring payout splits. The
+    /// recipient's own share is swapped into their payout token when one is set.
+    fn pay_claim(env: &Env, stream: &PayrollStream, amount: i128, pushed: bool) {
+        let remainder = Self::deduct_payout(env, stream, amount, pushed);
+        if remainder <= 0 || Self::try_swap_payout(env, stream, remainder) {
+            return;
+        }
+        token::Client::new(env, &stream.token).transfer(
+            &env.current_contract_address(),
+            &stream.recipient,
+            &remainder,
+        );
+    }
+
+    /// Swap `amount` of the stream token into the recipient's payout token and send it to
+    /// them. Returns `false`, leaving the tokens in escrow, if no payout token or router is
+    /// set, the minimum output overflows or the swap fails.
+    fn try_swap_payout(env: &Env, stream: &PayrollStream, amount: i128) -> bool {
+        let Some(ref payout_token) = stream.payout_token else {
+            return false;
+        };
+        let Some(router) = get_swap_router(env) else {
+            return false;
+        };
+
+        let Some(min_out) = amount.checked_mul(stream.payout_min_rate) else {
+            env.events().publish((symbol_short!("swap_fail"), stream.id), amount);
+            return false;
+        };
+        let min_out = min_out / PRICE_SCALE;
+
+        let contract_addr = env.current_contract_address();
+        let token_client = token::Client::new(env, &stream.token);
+        let expiration = env.ledger().sequence();
+
+        // The router pulls exactly `amount` through a single-use allowance, so a failed
+        // swap is rolled back without moving any tokens
+        token_client.approve(&contract_addr, &router, &amount, &expiration);
+        let swapped = SwapRouterClient::new(env, &router).try_swap_exact_in(
+            &contract_addr,
+            &stream.token,
+            payout_token,
+            &amount,
+            &min_out,
+            &stream.recipient,
+        );
+
+        match swapped {
+            Ok(Ok(amount_out)) => {
+                env.events().publish(
+                    (symbol_short!("swap"), stream.id),
+                    (amount, amount_out),
+                );
+                true
+            }
+            _ => {
+                token_client.approve(&contract_addr, &router, &0, &expiration);
+                env.events().publish(
+                    (symbol_short!("swap_fail"), stream.id),
+                    amount,
+                );
+                false
+            }
+        }
+    }
+
+    /// Check that there are at most `MAX_SPLITS` splits, each positive, and that together
+    /// with withholding they stay within 100%.
+    fn validate_splits(
+        splits: &Vec<PayoutSplit>,
+        withholding: &Vec<PayoutSplit>,
+    ) -> Result<(), StreamError> {
+        // Every payout, including the sender's cancellation, pays each split
+        if splits.len() > MAX_SPLITS {
+            return Err(StreamError::InvalidSplits);
+        }
+        let mut total: u32 = 0;
+        for split in splits.iter().chain(withholding.iter()) {
+            if split.bps == 0 || split.bps > MAX_BPS {
+                return Err(StreamError::InvalidSplits);
+            }
+            total += split.bps;
+        }
+        if total > MAX_BPS {
+            return Err(StreamError::InvalidSplits);
+        }
+        Ok(())
+    }
+
+    /// Settle a claim and transfer the tokens to the stream's recipient.
+    /// `pushed` marks a payout triggered by someone other than the recipient.
+    fn pay_out(
+        env: &Env,
+        stream_id: u32,
+        stream: &mut PayrollStream,
+        pushed: bool,
+    ) -> Result<i128, StreamError> {
+        let claimable = Self::settle_claim(env, stream)?;
+
+        Self::pay_claim(env, stream, claimable, pushed);
+
+        set_stream(env, stream_id, stream);
+
+        env.events().publish(
+            (symbol_short!("claim"), stream.recipient.clone()),
+            claimable,
+        );
+
+        Ok(claimable)
+    }
+
+    /// Cut the stream's segments at stream-clock time `at`: the segment spanning `at` is
+    /// truncated to what it accrues up to `at`, and any later segments are dropped.
+    /// Returns the amount removed, which is no longer scheduled to stream.
+    fn split_segments(stream: &mut PayrollStream, at: u64) -> i128 {
+        let mut kept = Vec::new(stream.segments.env());
+        let mut removed: i128 = 0;
+
+        for segment in stream.segments.iter() {
+            if segment.end_time <= at {
+                kept.push_back(segment);
+            } else if segment.start_time < at {
+                let accrued = Self::segment_accrued(&segment, at);
+                removed += segment.amount - accrued;
+                kept.push_back(StreamSegment {
+                    start_time: segment.start_time,
+                    end_time: at,
+                    amount: accrued,
+                });
+            } else {
+                removed += segment.amount;
+            }
+        }
+
+        stream.segments = kept;
+        removed
+    }
+
+    /// Amount a single segment has accrued by stream-clock time `at`.
+    fn segment_accrued(segment: &StreamSegment, at: u64) -> i128 {
+        if at <= segment.start_time {
+            return 0;
+        }
+        if at >= segment.end_time {
+            return segment.amount;
+        }
+        let elapsed = (at - segment.start_time) as i128;
+        let duration = (segment.end_time - segment.start_time) as i128;
+        segment.amount * elapsed / duration
+    }
+
+    /// Escrow a newly built stream's funds plus its creation fee and store it. Streams an
+    /// operator created for the org are funded through the org's allowance to this contract.
+    fn open_stream(env: &Env, stream: &PayrollStream) {
+        let contract_addr = env.current_contract_address();
+        let token_client = token::Client::new(env, &stream.token);
+        let fee = Self::creation_fee(stream, stream.total_amount);
+        let amount = stream.total_amount + fee;
+        if stream.operator == stream.sender {
+            token_client.transfer(&stream.sender, &contract_addr, &amount);
+        } else {
+            token_client.transfer_from(&contract_addr, &stream.sender, &contract_addr, &amount);
+        }
+        Self::accrue_fee(env, &stream.token, fee);
+
+        Self::store_new_stream(env, stream);
+    }
+
+    /// Store a new stream, index it under its sender and recipient and count it.
+    fn store_new_stream(env: &Env, stream: &PayrollStream) {
+        set_stream(env, stream.id, stream);
+        set_stream_count(env, stream.id + 1);
+        add_sender_stream(env, &stream.sender, stream.id);
+        add_recipient_stream(env, &stream.recipient, stream.id);
+    }
+
+    /// Validate creation parameters and build a new active stream.
+    fn build_stream(
+        env: &Env,
+        sender: &Address,
+        stream_id: u32,
+        params: &CreateStreamParams,
+    ) -> Result<PayrollStream, StreamError> {
+        if *sender == params.recipient {
+            return Err(StreamError::InvalidRecipient);
+        }
+        if params.total_amount <= 0 {
+            return Err(StreamError::InvalidAmount);
+        }
+        if params.end_time <= params.start_time {
+            return Err(StreamError::InvalidDuration);
+        }
+        if params.cliff_time != 0
+            && (params.cliff_time < params.start_time || params.cliff_time > params.end_time)
+        {
+            return Err(StreamError::InvalidCliff);
+        }
+        if let Some(arbiter) = &params.arbiter {
+            if *arbiter == *sender || *arbiter == params.recipient {
+                return Err(StreamError::InvalidArbiter);
+            }
+            // The recipient must agree to who will arbitrate their wages
+            params.recipient.require_auth();
+        }
+
+        let duration = params.end_time - params.start_time;
+        let rate_per_second = params.total_amount / (duration as i128);
+
+        Ok(PayrollStream {
+            id: stream_id,
+            sender: sender.clone(),
+            recipient: params.recipient.clone(),
+            token: params.token.clone(),
+            total_amount: params.total_amount,
+            claimed_amount: 0,
+            start_time: params.start_time,
+            end_time: params.end_time,
+            last_claim_time: params.start_time,
+            status: StreamStatus::Active,
+            rate_per_second,
+            paused_at: 0,
+            paused_duration: 0,
+            segments: Vec::from_array(
+                env,
+                [StreamSegment {
+                    start_time: params.start_time,
+                    end_time: params.end_time,
+                    amount: params.total_amount,
+                }],
+            ),
+            transferable: true,
+            kind: StreamKind::Linear,
+            cliff_time: params.cliff_time,
+            splits: Vec::new(env),
+            withholding: Vec::new(env),
+            cancel_policy: params.cancel_policy.clone(),
+            cancel_at: 0,
+            operator: sender.clone(),
+            advance_limit_bps: 0,
+            advance_outstanding: 0,
+            payout_token: None,
+            payout_min_rate: 0,
+            creation_fee: Self::creation_fee_terms(env),
+            claim_fee: Self::claim_fee_terms(env),
+            renewal: Renewal::disabled(),
+            arbiter: params.arbiter.clone(),
+            debt: 0,
+        })
+    }
+
+    /// Current position on the stream's own clock: ledger time with all paused
+    /// periods removed. Frozen at the pause instant while the stream is paused, and at
+    /// the effective time of a scheduled cancellation once it has passed.
+    fn stream_time(env: &Env, stream: &PayrollStream) -> u64 {
+        let mut now = if stream.status == StreamStatus::Paused
+            || stream.status == StreamStatus::Held
+        {
+            stream.paused_at
+        } else {
+            env.ledger().timestamp()
+        };
+        if stream.cancel_at != 0 {
+            now = now.min(stream.cancel_at);
+        }
+        now.saturating_sub(stream.paused_duration)
+    }
+
+    /// Whether the stream is still within its cliff period.
+    /// The cliff is measured on the stream clock, so pauses push it back.
+    fn before_cliff(env: &Env, stream: &PayrollStream) -> bool {
+        stream.cliff_time != 0 && Self::stream_time(env, stream) < stream.cliff_time
+    }
+
+    /// Calculate the amount a flow stream has earned so far, whether or not it is funded.
+    fn calculate_owed(env: &Env, stream: &PayrollStream) -> i128 {
+        let now = Self::stream_time(env, stream);
+        if now <= stream.start_time {
+            return 0;
+        }
+        stream.rate_per_second * ((now - stream.start_time) as i128)
+    }
+
+    /// Calculate the total amount accrued to the recipient so far, including claimed tokens.
+    /// Each segment accrues linearly over its own period; past segments are fixed.
+    /// Flow streams accrue at their rate, capped by the deposited balance.
+    fn calculate_accrued(env: &Env, stream: &PayrollStream) -> i128 {
+        if Self::before_cliff(env, stream) {
+            return 0;
+        }
+
+        let total_accrued = match stream.kind {
+            StreamKind::Linear => {
+                let now = Self::stream_time(env, stream);
+
+                // Recalculate from segment amounts and durations to minimize rounding errors
+                // Instead of using stored rate_per_second which might have rounding loss
+                let mut accrued: i128 = 0;
+                for segment in stream.segments.iter() {
+                    accrued += Self::segment_accrued(&segment, now);
+                }
+                accrued
+            }
+            StreamKind::Flow => Self::calculate_owed(env, stream),
+            StreamKind::Tranche(ref schedule) => Self::tranche_accrued(
+                stream,
+                schedule,
+                Self::stream_time(env, stream),
+                false,
+            ),
+            // Milestones are paid out as soon as they are released
+            StreamKind::Milestone(..) => stream.claimed_amount,
+        };
+
+        // Clamp to total_amount
+        if total_accrued > stream.total_amount {
+            stream.total_amount
+        } else {
+            total_accrued
+        }
+    }
+
+    /// Amount released by a tranche stream at stream-clock time `now`.
+    /// With `prorate`, the current period is also paid in proportion to time elapsed in it.
+    fn tranche_accrued(
+        stream: &PayrollStream,
+        schedule: &PaySchedule,
+        now: u64,
+        prorate: bool,
+    ) -> i128 {
+        let end_time = stream.end_time - stream.paused_duration;
+        if now >= end_time {
+            return stream.total_amount;
+        }
+
+        let interval = schedule.period.seconds();
+        let offset = if schedule.first_payday_offset > 0 {
+            schedule.first_payday_offset
+        } else {
+            interval
+        };
+        let first_payday = stream.start_time + offset;
+
+        // Paydays strictly before the end, plus the final payday at `end_time`
+        let early_paydays = if first_payday < end_time {
+            (end_time - 1 - first_payday) / interval + 1
+        } else {
+            0
+        };
+        let installment = stream.total_amount / ((early_paydays + 1) as i128);
+
+        let paid = if now < first_payday {
+            0
+        } else {
+            (now - first_payday) / interval + 1
+        };
+        let mut accrued = installment * (paid as i128);
+
+        if prorate {
+            let period_start = if paid == 0 {
+                stream.start_time
+            } else {
+                first_payday + (paid - 1) * interval
+            };
+            let period_end = if paid == 0 {
+                first_payday.min(end_time)
+            } else {
+                (period_start + interval).min(end_time)
+            };
+            let next_installment = if period_end == end_time {
+                stream.total_amount - installment * (early_paydays as i128)
+            } else {
+                installment
+            };
+            if now > period_start {
+                let elapsed = (now - period_start) as i128;
+                let length = (period_end - period_start) as i128;
+                accrued += next_installment * elapsed / length;
+            }
+        }
+
+        accrued
+    }
+
+    /// Calculate what the recipient has earned if the stream is cancelled now.
+    /// Equal to the accrued amount except for tranche streams that prorate on cancel.
+    fn calculate_cancel_accrued(env: &Env, stream: &PayrollStream) -> i128 {
+        if Self::before_cliff(env, stream) {
+            return 0;
+        }
+        if let StreamKind::Milestone(ref terms, ref milestones) = stream.kind {
+            // Milestones that would already auto-release belong to the recipient
+            let overdue: i128 = milestones
+                .iter()
+                .filter(|m| Self::milestone_releasable(env, terms, m))
+                .map(|m| m.amount)
+                .sum();
+            return stream.claimed_amount + overdue;
+        }
+        if let StreamKind::Tranche(ref schedule) = stream.kind {
+            if schedule.prorate_on_cancel {
+                let now = Self::stream_time(env, stream);
+                return Self::tranche_accrued(stream, schedule, now, true);
+            }
+        }
+        Self::calculate_accrued(env, stream)
+    }
+
+    /// Calculate the amount of tokens claimable by the recipient at the current time.
+    fn calculate_claimable(env: &Env, stream: &PayrollStream) -> i128 {
+        let total_accrued = Self::calculate_accrued(env, stream);
+
+        // Ensure we don't return negative claimable if something is wrong with state
+        if total_accrued < stream.claimed_amount {
+            return 0;
+        }
+
+        total_accrued - stream.claimed_amount
+    }
+
+    // ── Query Functions ──────────────────────────────────────────
+
+    /// Get a specific stream by ID.
+    pub fn get_stream(env: Env, stream_id: u32) -> Result<PayrollStream, StreamError> {
+        get_stream(&env, stream_id).ok_or(if get_archived_stream(&env, stream_id).is_some() {
+            StreamError::StreamArchived
+        } else {
+            StreamError::StreamNotFound
+        })
+    }
+
+    /// Get the claimable balance for a stream at the current time.
+    pub fn get_claimable(env: Env, stream_id: u32) -> Result<i128, StreamError> {
         let stream = get_stream(&env, stream_id)
             .ok_or(StreamError::StreamNotFound)?;
         Ok(Self::calculate_claimable(&env, &stream))
     }
 
+    /// Get a stream's payouts, oldest first, starting at `cursor` (0 for the first page).
+    /// Between one and `MAX_PAGE_SIZE` records are returned per page. Archived streams keep
+    /// their history.
+    pub fn get_claim_history(
+        env: Env,
+        stream_id: u32,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<ClaimHistoryPage, StreamError> {
+        if get_stream(&env, stream_id).is_none() && get_archived_stream(&env, stream_id).is_none() {
+            return Err(StreamError::StreamNotFound);
+        }
+
+        let count = get_claim_count(&env, stream_id);
+        let end = cursor.saturating_add(limit.clamp(1, MAX_PAGE_SIZE)).min(count);
+        let mut records: Vec<ClaimRecord> = Vec::new(&env);
+        for seq in cursor..end {
+            if let Some(record) = get_claim_record(&env, stream_id, seq) {
+                records.push_back(record);
+            }
+        }
+
+        Ok(ClaimHistoryPage {
+            records,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
+    }
+
+    /// Get how much the recipient has been advanced beyond what has accrued so far.
+    /// Later accruals repay this before anything more becomes claimable.
+    pub fn get_advance_debt(env: Env, stream_id: u32) -> Result<i128, StreamError> {
+        let stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+        if stream.status == StreamStatus::Cancelled {
+            return Ok(stream.advance_outstanding);
+        }
+        Ok((stream.claimed_amount - Self::calculate_accrued(&env, &stream)).max(0))
+    }
+
+    /// Get the amount a flow stream has accrued beyond its deposited balance.
+    /// Always zero for solvent streams and for non-flow streams. For a cancelled stream this
+    /// is the debt recorded at cancellation, which the sender never paid.
+    pub fn get_debt(env: Env, stream_id: u32) -> Result<i128, StreamError> {
+        let stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+        if stream.kind != StreamKind::Flow {
+            return Ok(0);
+        }
+        if stream.status == StreamStatus::Cancelled {
+            return Ok(stream.debt);
+        }
+        Ok((Self::calculate_owed(&env, &stream) - stream.total_amount).max(0))
+    }
+
+    /// Get the ledger time at which a flow stream's deposit runs out, or `u64::MAX` if it
+    /// lasts beyond the range of ledger time. For a paused stream this assumes it is
+    /// resumed now.
+    pub fn get_depletion_time(env: Env, stream_id: u32) -> Result<u64, StreamError> {
+        let stream = get_stream(&env, stream_id)
+            .ok_or(StreamError::StreamNotFound)?;
+        if stream.kind != StreamKind::Flow {
+            return Err(StreamError::InvalidStreamKind);
+        }
+
+        let funded_for =
+            u64::try_from(stream.total_amount / stream.rate_per_second).unwrap_or(u64::MAX);
+        let mut depletion = stream
+            .start_time
+            .saturating_add(funded_for)
+            .saturating_add(stream.paused_duration);
+        if stream.status == StreamStatus::Paused || stream.status == StreamStatus::Held {
+            depletion = depletion.saturating_add(env.ledger().timestamp() - stream.paused_at);
+        }
+        Ok(depletion)
+    }
+
+    /// Get an operator's budget and spending for `token`.
+    pub fn get_operator_budget(env: Env, operator: Address, token: Address) -> OperatorBudget {
+        get_operator_budget(&env, &operator, &token)
+    }
+
+    /// Whether `operator` currently holds the operator role.
+    pub fn is_operator(env: Env, operator: Address) -> bool {
+        is_operator(&env, &operator)
+    }
+
     /// Get the total number of streams created.
     pub fn get_stream_count(env: Env) -> u32 {
         get_stream_count(&env)
     }
 
-    /// Get all stream IDs created by a sender.
+    /// Get the first `MAX_PAGE_SIZE` stream IDs created by a sender.
+    /// Use `list_sender_streams` to page through more.
     pub fn get_streams_by_sender(env: Env, sender: Address) -> Vec<u32> {
-        get_sender_streams(&env, &sender)
+        get_sender_streams(&env, &sender, 0, MAX_PAGE_SIZE).0
     }
 
-    /// Get all stream IDs where the address is a recipient.
+    /// Get the first `MAX_PAGE_SIZE` stream IDs where the address is a recipient.
+    /// Use `list_recipient_streams` to page through more.
     pub fn get_streams_by_recipient(env: Env, recipient: Address) -> Vec<u32> {
-        get_recipient_streams(&env, &recipient)
+        get_recipient_streams(&env, &recipient, 0, MAX_PAGE_SIZE).0
+    }
+
+    /// List a sender's streams a page at a time, starting at `cursor` (0 for the first page).
+    /// At most `limit` index entries are examined per call, so a filtered page may hold
+    /// fewer streams; keep following `next_cursor` until it is `None`.
+    pub fn list_sender_streams(
+        env: Env,
+        sender: Address,
+        cursor: u32,
+        limit: u32,
+        status: Option<StreamStatus>,
+        token: Option<Address>,
+    ) -> StreamPage {
+        Self::list_streams(&env, &StreamIndex::Sender(sender), cursor, limit, status, token)
+    }
+
+    /// List a recipient's streams a page at a time. Paginates and filters like
+    /// `list_sender_streams`.
+    pub fn list_recipient_streams(
+        env: Env,
+        recipient: Address,
+        cursor: u32,
+        limit: u32,
+        status: Option<StreamStatus>,
+        token: Option<Address>,
+    ) -> StreamPage {
+        Self::list_streams(&env, &StreamIndex::Recipient(recipient), cursor, limit, status, token)
+    }
+
+    /// Get the fee charged on newly created streams, if one is configured.
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        get_fee_config(&env)
+    }
+
+    /// Get the fees collected in `token` that have not been withdrawn yet.
+    pub fn get_accrued_fees(env: Env, token: Address) -> i128 {
+        get_accrued_fees(&env, &token)
+    }
+
+    /// Get the swap router used for alternative payout tokens, if one is configured.
+    pub fn get_swap_router(env: Env) -> Option<Address> {
+        get_swap_router(&env)
     }
 
     /// Get the admin address.
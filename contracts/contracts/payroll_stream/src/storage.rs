@@ -1,6 +1,13 @@
-use soroban_sdk::{contracttype, Address, Env, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, TryFromVal, Val, Vec};
 
-use crate::types::PayrollStream;
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+use crate::types::{ClaimRecord, FeeConfig, OperatorBudget, PayrollStream};
 
 /// Keys used to store data in the contract's ledger storage.
 #[contracttype]
@@ -11,10 +18,32 @@ pub enum DataKey {
     StreamCount,
     /// A specific stream by ID — stored in Persistent storage.
     Stream(u32),
-    /// List of stream IDs for a specific sender — stored in Persistent storage.
-    SenderStreams(Address),
-    /// List of stream IDs for a specific recipient — stored in Persistent storage.
-    RecipientStreams(Address),
+    /// One chunk of the stream IDs for a specific sender — stored in Persistent storage.
+    SenderStreams(Address, u32),
+    /// Number of index chunks for a specific sender — stored in Persistent storage.
+    SenderChunks(Address),
+    /// One chunk of the stream IDs for a specific recipient — stored in Persistent storage.
+    RecipientStreams(Address, u32),
+    /// Number of index chunks for a specific recipient — stored in Persistent storage.
+    RecipientChunks(Address),
+    /// Whether a recipient refuses third-party payouts — stored in Persistent storage.
+    PushOptOut(Address),
+    /// Whether an address holds the operator role — stored in Persistent storage.
+    Operator(Address),
+    /// An operator's spending budget for one token — stored in Persistent storage.
+    OperatorBudget(Address, Address),
+    /// Hash of an archived stream's record — stored in Persistent storage.
+    ArchivedStream(u32),
+    /// Router used to pay out in a different token — stored in Instance storage.
+    SwapRouter,
+    /// Number of payouts recorded for a stream — stored in Persistent storage.
+    ClaimCount(u32),
+    /// A single recorded payout of a stream, by sequence number — stored in Persistent storage.
+    ClaimRecord(u32, u32),
+    /// Fee charged on newly created streams — stored in Instance storage.
+    FeeConfig,
+    /// Fees collected and not yet withdrawn, per token — stored in Persistent storage.
+    AccruedFees(Address),
 }
 
 // ── Admin helpers ────────────────────────────────────────────────
@@ -31,6 +60,34 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&DataKey::Admin, admin);
 }
 
+// ── Swap router helpers ──────────────────────────────────────────
+
+pub fn get_swap_router(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::SwapRouter)
+}
+
+pub fn set_swap_router(env: &Env, router: &Address) {
+    env.storage().instance().set(&DataKey::SwapRouter, router);
+}
+
+// ── Fee helpers ──────────────────────────────────────────────────
+
+pub fn get_fee_config(env: &Env) -> Option<FeeConfig> {
+    env.storage().instance().get(&DataKey::FeeConfig)
+}
+
+pub fn set_fee_config(env: &Env, config: &FeeConfig) {
+    env.storage().instance().set(&DataKey::FeeConfig, config);
+}
+
+pub fn get_accrued_fees(env: &Env, token: &Address) -> i128 {
+    get_persistent(env, &DataKey::AccruedFees(token.clone())).unwrap_or(0)
+}
+
+pub fn set_accrued_fees(env: &Env, token: &Address, amount: i128) {
+    set_persistent(env, &DataKey::AccruedFees(token.clone()), &amount);
+}
+
 // ── Stream count helpers ─────────────────────────────────────────
 
 pub fn get_stream_count(env: &Env) -> u32 {
@@ -44,41 +101,276 @@ pub fn set_stream_count(env: &Env, count: u32) {
 // ── Stream helpers ───────────────────────────────────────────────
 
 pub fn get_stream(env: &Env, id: u32) -> Option<PayrollStream> {
-    env.storage().persistent().get(&DataKey::Stream(id))
+    let stream = env.storage().persistent().get(&DataKey::Stream(id));
+    if stream.is_some() {
+        extend_stream_ttl(env, id);
+    }
+    stream
 }
 
 pub fn set_stream(env: &Env, id: u32, stream: &PayrollStream) {
     env.storage().persistent().set(&DataKey::Stream(id), stream);
+    extend_stream_ttl(env, id);
+    extend_instance_ttl(env);
+}
+
+pub fn remove_stream(env: &Env, id: u32) {
+    env.storage().persistent().remove(&DataKey::Stream(id));
+}
+
+// ── Archive helpers ──────────────────────────────────────────────
+
+pub fn get_archived_stream(env: &Env, id: u32) -> Option<BytesN<32>> {
+    get_persistent(env, &DataKey::ArchivedStream(id))
+}
+
+pub fn set_archived_stream(env: &Env, id: u32, hash: &BytesN<32>) {
+    set_persistent(env, &DataKey::ArchivedStream(id), hash);
+}
+
+pub fn remove_archived_stream(env: &Env, id: u32) {
+    env.storage().persistent().remove(&DataKey::ArchivedStream(id));
 }
 
 // ── Index helpers for sender/recipient stream lookups ────────────
 
-pub fn get_sender_streams(env: &Env, sender: &Address) -> Vec<u32> {
+/// Maximum number of stream IDs stored in one index chunk, so that no single
+/// ledger entry grows without bound.
+pub const INDEX_CHUNK_SIZE: u32 = 100;
+/// Placeholder left in an index slot whose stream was removed.
+const REMOVED_INDEX_ENTRY: u32 = u32::MAX;
+
+/// A per-address list of stream IDs, stored as a sequence of chunks.
+pub enum StreamIndex {
+    Sender(Address),
+    Recipient(Address),
+}
+
+impl StreamIndex {
+    fn chunk_key(&self, chunk: u32) -> DataKey {
+        match self {
+            StreamIndex::Sender(addr) => DataKey::SenderStreams(addr.clone(), chunk),
+            StreamIndex::Recipient(addr) => DataKey::RecipientStreams(addr.clone(), chunk),
+        }
+    }
+
+    fn count_key(&self) -> DataKey {
+        match self {
+            StreamIndex::Sender(addr) => DataKey::SenderChunks(addr.clone()),
+            StreamIndex::Recipient(addr) => DataKey::RecipientChunks(addr.clone()),
+        }
+    }
+}
+
+pub fn get_index_chunk_count(env: &Env, index: &StreamIndex) -> u32 {
+    let key = index.count_key();
+    match env.storage().persistent().get(&key) {
+        Some(count) => {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            count
+        }
+        None => 0,
+    }
+}
+
+pub fn get_index_chunk(env: &Env, index: &StreamIndex, chunk: u32) -> Vec<u32> {
+    let key = index.chunk_key(chunk);
+    match env.storage().persistent().get(&key) {
+        Some(ids) => {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            ids
+        }
+        None => Vec::new(env),
+    }
+}
+
+fn set_index_chunk(env: &Env, index: &StreamIndex, chunk: u32, ids: &Vec<u32>) {
+    let key = index.chunk_key(chunk);
+    env.storage().persistent().set(&key, ids);
     env.storage()
         .persistent()
-        .get(&DataKey::SenderStreams(sender.clone()))
-        .unwrap_or(Vec::new(env))
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn add_to_index(env: &Env, index: &StreamIndex, stream_id: u32) {
+    let count = get_index_chunk_count(env, index);
+    let mut chunk = count.saturating_sub(1);
+    let mut ids = get_index_chunk(env, index, chunk);
+    if count == 0 || ids.len() >= INDEX_CHUNK_SIZE {
+        chunk = count;
+        ids = Vec::new(env);
+        let count_key = index.count_key();
+        env.storage().persistent().set(&count_key, &(count + 1));
+        env.storage()
+            .persistent()
+            .extend_ttl(&count_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+    ids.push_back(stream_id);
+    set_index_chunk(env, index, chunk, &ids);
+}
+
+/// Read up to `limit` index entries starting at `cursor`, which encodes
+/// `chunk * INDEX_CHUNK_SIZE + offset`. Removed entries are skipped but count towards the
+/// limit. At least one entry is read, so the cursor always moves forward.
+/// Returns the IDs read and the cursor to resume from, if entries remain.
+pub fn get_index_page(
+    env: &Env,
+    index: &StreamIndex,
+    cursor: u32,
+    limit: u32,
+) -> (Vec<u32>, Option<u32>) {
+    let chunk_count = get_index_chunk_count(env, index);
+    let mut ids = Vec::new(env);
+    let mut remaining = limit.max(1);
+    let mut chunk = cursor / INDEX_CHUNK_SIZE;
+    let mut offset = cursor % INDEX_CHUNK_SIZE;
+
+    while chunk < chunk_count {
+        let entries = get_index_chunk(env, index, chunk);
+        while offset < entries.len() {
+            if remaining == 0 {
+                return (ids, Some(chunk * INDEX_CHUNK_SIZE + offset));
+            }
+            remaining -= 1;
+
+            let stream_id = entries.get_unchecked(offset);
+            offset += 1;
+            if stream_id != REMOVED_INDEX_ENTRY {
+                ids.push_back(stream_id);
+            }
+        }
+        chunk += 1;
+        offset = 0;
+    }
+
+    (ids, None)
+}
+
+pub fn get_sender_streams(
+    env: &Env,
+    sender: &Address,
+    cursor: u32,
+    limit: u32,
+) -> (Vec<u32>, Option<u32>) {
+    get_index_page(env, &StreamIndex::Sender(sender.clone()), cursor, limit)
 }
 
 pub fn add_sender_stream(env: &Env, sender: &Address, stream_id: u32) {
-    let mut streams = get_sender_streams(env, sender);
-    streams.push_back(stream_id);
-    env.storage()
-        .persistent()
-        .set(&DataKey::SenderStreams(sender.clone()), &streams);
+    add_to_index(env, &StreamIndex::Sender(sender.clone()), stream_id);
 }
 
-pub fn get_recipient_streams(env: &Env, recipient: &Address) -> Vec<u32> {
-    env.storage()
-        .persistent()
-        .get(&DataKey::RecipientStreams(recipient.clone()))
-        .unwrap_or(Vec::new(env))
+pub fn get_recipient_streams(
+    env: &Env,
+    recipient: &Address,
+    cursor: u32,
+    limit: u32,
+) -> (Vec<u32>, Option<u32>) {
+    get_index_page(env, &StreamIndex::Recipient(recipient.clone()), cursor, limit)
 }
 
 pub fn add_recipient_stream(env: &Env, recipient: &Address, stream_id: u32) {
-    let mut streams = get_recipient_streams(env, recipient);
-    streams.push_back(stream_id);
+    add_to_index(env, &StreamIndex::Recipient(recipient.clone()), stream_id);
+}
+
+/// Remove a stream from a recipient's index. The slot is left as a `REMOVED_INDEX_ENTRY`
+/// rather than shifting later entries, so page cursors handed out earlier stay valid.
+pub fn remove_recipient_stream(env: &Env, recipient: &Address, stream_id: u32) {
+    let index = StreamIndex::Recipient(recipient.clone());
+    for chunk in 0..get_index_chunk_count(env, &index) {
+        let mut ids = get_index_chunk(env, &index, chunk);
+        if let Some(position) = ids.first_index_of(stream_id) {
+            ids.set(position, REMOVED_INDEX_ENTRY);
+            set_index_chunk(env, &index, chunk, &ids);
+            return;
+        }
+    }
+}
+
+// ── Claim history helpers ────────────────────────────────────────
+
+pub fn get_claim_count(env: &Env, stream_id: u32) -> u32 {
+    get_persistent(env, &DataKey::ClaimCount(stream_id)).unwrap_or(0)
+}
+
+pub fn get_claim_record(env: &Env, stream_id: u32, seq: u32) -> Option<ClaimRecord> {
+    get_persistent(env, &DataKey::ClaimRecord(stream_id, seq))
+}
+
+pub fn add_claim_record(env: &Env, stream_id: u32, record: &ClaimRecord) {
+    let seq = get_claim_count(env, stream_id);
+    set_persistent(env, &DataKey::ClaimRecord(stream_id, seq), record);
+    set_persistent(env, &DataKey::ClaimCount(stream_id), &(seq + 1));
+}
+
+// ── Push-payment preference helpers ──────────────────────────────
+
+pub fn get_push_opt_out(env: &Env, recipient: &Address) -> bool {
+    get_persistent(env, &DataKey::PushOptOut(recipient.clone())).unwrap_or(false)
+}
+
+pub fn set_push_opt_out(env: &Env, recipient: &Address, opt_out: bool) {
+    set_persistent(env, &DataKey::PushOptOut(recipient.clone()), &opt_out);
+}
+
+// ── Operator helpers ─────────────────────────────────────────────
+
+pub fn is_operator(env: &Env, operator: &Address) -> bool {
+    get_persistent(env, &DataKey::Operator(operator.clone())).unwrap_or(false)
+}
+
+pub fn set_operator(env: &Env, operator: &Address, active: bool) {
+    set_persistent(env, &DataKey::Operator(operator.clone()), &active);
+}
+
+pub fn get_operator_budget(env: &Env, operator: &Address, token: &Address) -> OperatorBudget {
+    get_persistent(env, &DataKey::OperatorBudget(operator.clone(), token.clone()))
+        .unwrap_or(OperatorBudget { limit: 0, spent: 0 })
+}
+
+pub fn set_operator_budget(
+    env: &Env,
+    operator: &Address,
+    token: &Address,
+    budget: &OperatorBudget,
+) {
+    set_persistent(env, &DataKey::OperatorBudget(operator.clone(), token.clone()), budget);
+}
+
+// ── TTL helpers ──────────────────────────────────────────────────
+
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Read a persistent entry, extending its TTL if it exists.
+fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+    value
+}
+
+/// Write a persistent entry and extend its TTL.
+fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
     env.storage()
         .persistent()
-        .set(&DataKey::RecipientStreams(recipient.clone()), &streams);
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub fn extend_stream_ttl(env: &Env, id: u32) {
+    env.storage().persistent().extend_ttl(
+        &DataKey::Stream(id),
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
 }
@@ -0,0 +1,19 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface the payroll contract expects from a swap router contract.
+/// Any contract exposing this function can be configured via `set_swap_router`.
+#[contractclient(name = "SwapRouterClient")]
+pub trait SwapRouter {
+    /// Swap exactly `amount_in` of `token_in`, pulled from `from` using its allowance to the
+    /// router, for at least `min_out` of `token_out` sent to `to`. Returns the amount sent.
+    /// Must fail rather than deliver less than `min_out`.
+    fn swap_exact_in(
+        env: Env,
+        from: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        to: Address,
+    ) -> i128;
+}
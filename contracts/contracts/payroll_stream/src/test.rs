@@ -1,8 +1,14 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, testutils::Ledger, Address, Env, Vec, token};
-use types::StreamStatus;
+use soroban_sdk::{
+    testutils::storage::Persistent as _, testutils::Address as _, testutils::Ledger, token, Address,
+    BytesN, Env, Vec,
+};
+use types::{
+    CancelPolicy, FeeTiming, MilestoneStatus, MilestoneTimeout, PayPeriod, PaySchedule, PayoutSplit,
+    Renewal, RenewalFunding, StreamClaim, StreamKind, StreamSegment, StreamSettlement, StreamStatus,
+};
 
 fn setup_env() -> (Env, Address, PayrollStreamContractClient<'static>) {
     let env = Env::default();
@@ -13,6 +19,24 @@ fn setup_env() -> (Env, Address, PayrollStreamContractClient<'static>) {
     (env, admin, client)
 }
 
+fn create_token_contract<'a>(e: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
+    let contract_addr = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(e, &contract_addr)
+}
+
+fn create_token_client<'a>(e: &Env, contract_addr: &Address) -> token::Client<'a> {
+    token::Client::new(e, contract_addr)
+}
+
+/// Create a token and mint `amount` to `holder` so it can fund stream escrow.
+fn create_funded_token(e: &Env, holder: &Address, amount: i128) -> Address {
+    let token_admin_client = create_token_contract(e, &Address::generate(e));
+    token_admin_client.mint(holder, &amount);
+    token_admin_client.address.clone()
+}
+
 #[test]
 fn test_initialize() {
     let (_env, admin, client) = setup_env();
@@ -34,7 +58,7 @@ fn test_create_stream() {
     let (env, admin, client) = setup_env();
     let sender = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let token = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
 
     client.initialize(&admin);
 
@@ -62,7 +86,7 @@ fn test_create_stream() {
 fn test_create_batch_streams() {
     let (env, admin, client) = setup_env();
     let sender = Address::generate(&env);
-    let token = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
 
     client.initialize(&admin);
 
@@ -79,6 +103,9 @@ fn test_create_batch_streams() {
         total_amount: 10000,
         start_time: 1000,
         end_time: 2000,
+        cliff_time: 0,
+        cancel_policy: CancelPolicy::Cancelable,
+        arbiter: None,
     });
 
     // Stream 2
@@ -88,6 +115,9 @@ fn test_create_batch_streams() {
         total_amount: 20000,
         start_time: 1000,
         end_time: 3000,
+        cliff_time: 0,
+        cancel_policy: CancelPolicy::Cancelable,
+        arbiter: None,
     });
 
     let stream_ids = client.create_batch_streams(&sender, &streams);
@@ -108,7 +138,7 @@ fn test_calculate_claimable() {
     let (env, admin, client) = setup_env();
     let sender = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let token = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
 
     client.initialize(&admin);
 
@@ -134,15 +164,6 @@ fn test_calculate_claimable() {
     assert_eq!(claimable, 5000); // 50% of 10000
 }
 
-fn create_token_contract<'a>(e: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
-    let contract_addr = e.register_stellar_asset_contract(admin.clone());
-    token::StellarAssetClient::new(e, &contract_addr)
-}
-
-fn create_token_client<'a>(e: &Env, contract_addr: &Address) -> token::Client<'a> {
-    token::Client::new(e, contract_addr)
-}
-
 #[test]
 fn test_cancel_stream() {
     let (env, admin, client) = setup_env();
@@ -156,7 +177,6 @@ fn test_cancel_stream() {
     let token_client = create_token_client(&env, &token);
     
     token_admin_client.mint(&sender, &20000);
-    token_admin_client.mint(&client.address, &10000);
 
     client.initialize(&admin);
 
@@ -184,14 +204,9 @@ fn test_cancel_stream() {
     
     // Recipient gets 0
     assert_eq!(token_client.balance(&recipient), 0);
-    // Sender gets refund (assumes contract had funds)
-    // Contract had 10000, refunded 10000 to sender.
-    // Sender started with 20000. 
-    // (Wait, create_stream didn't transfer FROM sender yet in code, but I simulated contract holding 10000)
-    // So sender balance should be 20000 + 10000 = 30000? 
-    // No, sender starts with 20000. Contract starts with 10000 (minted directly).
-    // Refund adds 10000 to sender. Total 30000.
-    assert_eq!(token_client.balance(&sender), 30000);
+    // Sender escrowed 10000 of its 20000 and gets all of it back
+    assert_eq!(token_client.balance(&sender), 20000);
+    assert_eq!(token_client.balance(&client.address), 0);
 }
 
 #[test]
@@ -205,7 +220,7 @@ fn test_cancel_stream_midway() {
     let token = token_admin_client.address.clone();
     let token_client = create_token_client(&env, &token);
     
-    token_admin_client.mint(&client.address, &10000); // Simulate contract holding funds
+    token_admin_client.mint(&sender, &10000);
 
     client.initialize(&admin);
 
@@ -249,7 +264,7 @@ fn test_cancel_stream_after_end() {
     let token = token_admin_client.address.clone();
     let token_client = create_token_client(&env, &token);
     
-    token_admin_client.mint(&client.address, &10000);
+    token_admin_client.mint(&sender, &10000);
 
     client.initialize(&admin);
 
@@ -278,3 +293,3134 @@ fn test_cancel_stream_after_end() {
     // Sender should have 0 refund
     assert_eq!(token_client.balance(&sender), 0);
 }
+
+#[test]
+fn test_pause_and_resume_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    // Pause at 25%
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1250;
+    });
+    client.pause_stream(&sender, &stream_id);
+    assert_eq!(client.get_stream(&stream_id).status, StreamStatus::Paused);
+
+    // Accrual is frozen during the pause
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1750;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 2500);
+
+    // Resume after 500 seconds of unpaid leave — end time shifts by the same amount
+    client.resume_stream(&sender, &stream_id);
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.status, StreamStatus::Active);
+    assert_eq!(stream.end_time, 2500);
+    assert_eq!(stream.paused_duration, 500);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2500;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 10000);
+}
+
+#[test]
+fn test_claim_during_pause_pays_pre_pause_accrual() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1400;
+    });
+    client.pause_stream(&sender, &stream_id);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1900;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 4000);
+    assert_eq!(client.get_claimable(&stream_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_resume_active_stream_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    client.resume_stream(&sender, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_pause_by_non_sender_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    client.pause_stream(&recipient, &stream_id);
+}
+
+// ── Escrow ───────────────────────────────────────────────────────
+
+/// The contract must hold exactly the unclaimed balance of every live stream in `token`.
+fn assert_escrow_invariant(env: &Env, client: &PayrollStreamContractClient, token: &Address) {
+    let mut unclaimed: i128 = 0;
+    for id in 0..client.get_stream_count() {
+        let stream = client.get_stream(&id);
+        if stream.token == *token && stream.status != StreamStatus::Cancelled {
+            unclaimed += stream.total_amount - stream.claimed_amount;
+        }
+    }
+    assert_eq!(create_token_client(env, token).balance(&client.address), unclaimed);
+}
+
+#[test]
+fn test_create_stream_escrows_and_claim_pays_out() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+    assert_eq!(token_client.balance(&sender), 0);
+    assert_eq!(token_client.balance(&client.address), 10000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1300;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 3000);
+    assert_eq!(token_client.balance(&recipient), 3000);
+    assert_escrow_invariant(&env, &client, &token);
+}
+
+#[test]
+#[should_panic]
+fn test_create_stream_without_funds_fails() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 500);
+
+    client.initialize(&admin);
+
+    client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+}
+
+#[test]
+fn test_batch_escrow_per_token() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let usdc = create_funded_token(&env, &sender, 30000);
+    let xlm = create_funded_token(&env, &sender, 5000);
+
+    client.initialize(&admin);
+
+    let mut streams = Vec::new(&env);
+    streams.push_back(CreateStreamParams {
+        recipient: Address::generate(&env),
+        token: usdc.clone(),
+        total_amount: 10000,
+        start_time: 1000,
+        end_time: 2000,
+        cliff_time: 0,
+        cancel_policy: CancelPolicy::Cancelable,
+        arbiter: None,
+    });
+    streams.push_back(CreateStreamParams {
+        recipient: Address::generate(&env),
+        token: xlm.clone(),
+        total_amount: 5000,
+        start_time: 1000,
+        end_time: 2000,
+        cliff_time: 0,
+        cancel_policy: CancelPolicy::Cancelable,
+        arbiter: None,
+    });
+    streams.push_back(CreateStreamParams {
+        recipient: Address::generate(&env),
+        token: usdc.clone(),
+        total_amount: 20000,
+        start_time: 1000,
+        end_time: 3000,
+        cliff_time: 0,
+        cancel_policy: CancelPolicy::Cancelable,
+        arbiter: None,
+    });
+
+    client.create_batch_streams(&sender, &streams);
+
+    assert_eq!(create_token_client(&env, &usdc).balance(&sender), 0);
+    assert_eq!(create_token_client(&env, &xlm).balance(&sender), 0);
+    assert_escrow_invariant(&env, &client, &usdc);
+    assert_escrow_invariant(&env, &client, &xlm);
+}
+
+#[test]
+fn test_escrow_invariant_across_lifecycle() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 100_000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let a = client.create_stream(&sender, &alice, &token, &10000_i128, &1000_u64, &2000_u64);
+    let b = client.create_stream(&sender, &bob, &token, &7000_i128, &1000_u64, &3000_u64);
+    let c = client.create_stream(&sender, &carol, &token, &3333_i128, &1500_u64, &2500_u64);
+    assert_escrow_invariant(&env, &client, &token);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1700;
+    });
+    client.claim(&alice, &a);
+    client.claim(&bob, &b);
+    client.claim(&carol, &c);
+    assert_escrow_invariant(&env, &client, &token);
+
+    client.cancel_stream(&sender, &b);
+    assert_escrow_invariant(&env, &client, &token);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2600;
+    });
+    client.claim(&alice, &a);
+    client.claim(&carol, &c);
+    assert_escrow_invariant(&env, &client, &token);
+
+    assert_eq!(client.get_stream(&a).status, StreamStatus::Completed);
+    assert_eq!(client.get_stream(&c).status, StreamStatus::Completed);
+    assert_eq!(create_token_client(&env, &token).balance(&client.address), 0);
+}
+
+// ── Top-up ───────────────────────────────────────────────────────
+
+#[test]
+fn test_top_up_and_extend_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 15000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.claim(&recipient, &stream_id);
+
+    // Renewal: 5000 more, running until 2500
+    client.top_up_stream(&sender, &stream_id, &5000, &2500);
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.total_amount, 15000);
+    assert_eq!(stream.end_time, 2500);
+    assert_eq!(stream.rate_per_second, 10);
+    assert_eq!(client.get_claimable(&stream_id), 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2500;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 10000);
+    client.claim(&recipient, &stream_id);
+    assert_escrow_invariant(&env, &client, &token);
+    assert_eq!(client.get_stream(&stream_id).status, StreamStatus::Completed);
+}
+
+#[test]
+fn test_top_up_without_extending_raises_rate() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 15000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.top_up_stream(&sender, &stream_id, &5000, &2000);
+
+    // 5000 accrued before the top-up is unchanged; the remaining 10000 streams over 500s
+    assert_eq!(client.get_claimable(&stream_id), 5000);
+    assert_eq!(client.get_stream(&stream_id).rate_per_second, 20);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1750;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 10000);
+    assert_escrow_invariant(&env, &client, &token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_top_up_cannot_shorten_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 15000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    client.top_up_stream(&sender, &stream_id, &5000, &1500);
+}
+
+// ── Rate changes ─────────────────────────────────────────────────
+
+#[test]
+fn test_update_rate_scheduled_raise() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 15000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    // Raise from 10/s to 20/s, effective halfway through
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.update_rate(&sender, &stream_id, &20, &1500);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.total_amount, 15000);
+    assert_eq!(stream.rate_per_second, 20);
+    assert_eq!(stream.segments.len(), 2);
+    assert_eq!(
+        stream.segments.get(0).unwrap(),
+        StreamSegment {
+            start_time: 1000,
+            end_time: 1500,
+            amount: 5000,
+        }
+    );
+    assert_eq!(token_client.balance(&sender), 0);
+
+    // Old rate still applies until the raise takes effect
+    assert_eq!(client.get_claimable(&stream_id), 2000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1800;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 11000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 15000);
+    assert_escrow_invariant(&env, &client, &token);
+}
+
+#[test]
+fn test_update_rate_pay_cut_refunds_sender() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1400;
+    });
+    client.claim(&recipient, &stream_id);
+    client.update_rate(&sender, &stream_id, &5, &1600);
+
+    // Accrued to 1600 at 10/s, then 400s at 5/s
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.total_amount, 8000);
+    assert_eq!(token_client.balance(&sender), 2000);
+
+    // A second change supersedes the first from its own effective time
+    client.update_rate(&sender, &stream_id, &8, &1500);
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.segments.len(), 2);
+    assert_eq!(stream.total_amount, 9000);
+    assert_eq!(token_client.balance(&sender), 1000);
+    assert_escrow_invariant(&env, &client, &token);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 5000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_update_rate_cannot_be_retroactive() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.update_rate(&sender, &stream_id, &20, &1200);
+}
+
+// ── Transfers ────────────────────────────────────────────────────
+
+#[test]
+fn test_transfer_stream_moves_claim_rights() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let old_wallet = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &old_wallet,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.transfer_stream(&old_wallet, &stream_id, &new_wallet);
+
+    assert_eq!(client.get_stream(&stream_id).recipient, new_wallet);
+    assert_eq!(client.get_streams_by_recipient(&old_wallet).len(), 0);
+    assert_eq!(client.get_streams_by_recipient(&new_wallet).len(), 1);
+
+    // Unclaimed accrual moves with the stream
+    assert_eq!(client.claim(&new_wallet, &stream_id), 5000);
+    assert_eq!(token_client.balance(&new_wallet), 5000);
+    assert_eq!(token_client.balance(&old_wallet), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_transfer_locked_stream_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    client.set_transferable(&sender, &stream_id, &false);
+    assert!(!client.get_stream(&stream_id).transferable);
+
+    client.transfer_stream(&recipient, &stream_id, &Address::generate(&env));
+}
+
+// ── Flow streams ─────────────────────────────────────────────────
+
+#[test]
+fn test_flow_stream_accrues_until_depleted() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_flow_stream(&sender, &recipient, &token, &10, &1000, &5000);
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.kind, StreamKind::Flow);
+    assert_eq!(client.get_depletion_time(&stream_id), 1500);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1300;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 3000);
+
+    // Past depletion: the balance is exhausted and the shortfall is debt
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1800;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 2000);
+    assert_eq!(client.get_debt(&stream_id), 3000);
+    client.claim(&recipient, &stream_id);
+    assert_eq!(client.get_stream(&stream_id).status, StreamStatus::Active);
+
+    // Refilling settles the debt first
+    client.deposit_flow(&sender, &stream_id, &10000);
+    assert_eq!(client.get_debt(&stream_id), 0);
+    assert_eq!(client.get_claimable(&stream_id), 3000);
+    assert_eq!(client.get_depletion_time(&stream_id), 2500);
+    assert_escrow_invariant(&env, &client, &token);
+}
+
+#[test]
+fn test_depletion_time_saturates_for_large_deposits() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let deposit: i128 = 1 << 70;
+    let token = create_funded_token(&env, &sender, deposit);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_flow_stream(&sender, &recipient, &token, &1, &1000, &deposit);
+    assert_eq!(client.get_depletion_time(&stream_id), u64::MAX);
+}
+
+#[test]
+fn test_cancel_flow_stream_refunds_balance() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 5000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_flow_stream(&sender, &recipient, &token, &10, &1000, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    assert_eq!(token_client.balance(&recipient), 2000);
+    assert_eq!(token_client.balance(&sender), 3000);
+}
+
+#[test]
+fn test_cancel_insolvent_flow_stream_records_debt() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 5000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_flow_stream(&sender, &recipient, &token, &10, &1000, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1800;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    // The unpaid accrual stays on record and no longer grows
+    assert_eq!(token_client.balance(&recipient), 5000);
+    assert_eq!(client.get_stream(&stream_id).debt, 3000);
+    env.ledger().with_mut(|li| {
+        li.timestamp = 5000;
+    });
+    assert_eq!(client.get_debt(&stream_id), 3000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_top_up_flow_stream_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_flow_stream(&sender, &recipient, &token, &10, &1000, &5000);
+    client.top_up_stream(&sender, &stream_id, &1000, &u64::MAX);
+}
+
+// ── Tranche streams ──────────────────────────────────────────────
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn weekly_schedule(prorate_on_cancel: bool) -> PaySchedule {
+    PaySchedule {
+        period: PayPeriod::Weekly,
+        first_payday_offset: 0,
+        prorate_on_cancel,
+    }
+}
+
+#[test]
+fn test_tranche_stream_releases_on_paydays() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 4000);
+
+    client.initialize(&admin);
+
+    // Four weekly paydays of 1000
+    let stream_id = client.create_tranche_stream(
+        &sender,
+        &CreateStreamParams {
+            recipient: recipient.clone(),
+            token: token.clone(),
+            total_amount: 4000,
+            start_time: DAY,
+            end_time: 29 * DAY,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        },
+        &weekly_schedule(false),
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 7 * DAY;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 8 * DAY;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 1000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 21 * DAY;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 2000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 29 * DAY;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 2000);
+    assert_eq!(client.get_stream(&stream_id).status, StreamStatus::Completed);
+}
+
+#[test]
+fn test_tranche_stream_first_payday_offset() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 9000);
+
+    client.initialize(&admin);
+
+    // Monthly pay, first payday 10 days in: paydays at day 10, 40 and the end at day 60
+    let schedule = PaySchedule {
+        period: PayPeriod::Monthly,
+        first_payday_offset: 10 * DAY,
+        prorate_on_cancel: false,
+    };
+    let stream_id = client.create_tranche_stream(
+        &sender,
+        &CreateStreamParams {
+            recipient: recipient.clone(),
+            token: token.clone(),
+            total_amount: 9000,
+            start_time: 0,
+            end_time: 60 * DAY,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        },
+        &schedule,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 10 * DAY;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 3000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 45 * DAY;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 6000);
+}
+
+#[test]
+fn test_tranche_stream_cancel_policies() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 8000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let strict = client.create_tranche_stream(
+        &sender,
+        &CreateStreamParams {
+            recipient: recipient.clone(),
+            token: token.clone(),
+            total_amount: 4000,
+            start_time: 0,
+            end_time: 28 * DAY,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        },
+        &weekly_schedule(false),
+    );
+    let prorated = client.create_tranche_stream(
+        &sender,
+        &CreateStreamParams {
+            recipient: recipient.clone(),
+            token: token.clone(),
+            total_amount: 4000,
+            start_time: 0,
+            end_time: 28 * DAY,
+            cliff_time: 0,
+            cancel_policy: CancelPolicy::Cancelable,
+            arbiter: None,
+        },
+        &weekly_schedule(true),
+    );
+
+    // Halfway through the second week
+    env.ledger().with_mut(|li| {
+        li.timestamp = 10 * DAY + DAY / 2;
+    });
+    client.cancel_stream(&sender, &strict);
+    assert_eq!(token_client.balance(&recipient), 1000);
+
+    client.cancel_stream(&sender, &prorated);
+    assert_eq!(token_client.balance(&recipient), 2500);
+    assert_eq!(token_client.balance(&sender), 5500);
+}
+
+// ── Cliffs ───────────────────────────────────────────────────────
+
+fn cliff_params(recipient: &Address, token: &Address) -> CreateStreamParams {
+    CreateStreamParams {
+        recipient: recipient.clone(),
+        token: token.clone(),
+        total_amount: 10000,
+        start_time: 1000,
+        end_time: 2000,
+        cliff_time: 1300,
+        cancel_policy: CancelPolicy::Cancelable,
+        arbiter: None,
+    }
+}
+
+#[test]
+fn test_cliff_unlocks_accrual_in_one_lump() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream_with_params(&sender, &cliff_params(&recipient, &token));
+    assert_eq!(client.get_stream(&stream_id).cliff_time, 1300);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1299;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1300;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 3000);
+}
+
+#[test]
+fn test_cancel_before_cliff_refunds_in_full() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let mut streams = Vec::new(&env);
+    streams.push_back(cliff_params(&recipient, &token));
+    let stream_ids = client.create_batch_streams(&sender, &streams);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.cancel_stream(&sender, &stream_ids.get(0).unwrap());
+
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(token_client.balance(&sender), 10000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_cliff_after_end_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let mut params = cliff_params(&recipient, &token);
+    params.cliff_time = 2500;
+    client.create_stream_with_params(&sender, &params);
+}
+
+// ── Push payments ────────────────────────────────────────────────
+
+#[test]
+fn test_withdraw_for_pays_recipient() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let bot = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1400;
+    });
+    assert_eq!(client.withdraw_for(&bot, &stream_id), 4000);
+    assert_eq!(token_client.balance(&recipient), 4000);
+    assert_eq!(token_client.balance(&bot), 0);
+}
+
+#[test]
+fn test_withdraw_many_skips_unpayable_streams() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let bot = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 30000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let a = client.create_stream(&sender, &alice, &token, &10000_i128, &1000_u64, &2000_u64);
+    let b = client.create_stream(&sender, &bob, &token, &10000_i128, &1000_u64, &2000_u64);
+    let c = client.create_stream(&sender, &carol, &token, &10000_i128, &1500_u64, &2500_u64);
+
+    client.set_push_opt_out(&bob, &true);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    let paid = client.withdraw_many(&bot, &Vec::from_array(&env, [a, b, 99, c]));
+    assert_eq!(paid, Vec::from_array(&env, [5000_i128, 0, 0, 0]));
+    assert_eq!(token_client.balance(&alice), 5000);
+    assert_eq!(token_client.balance(&bob), 0);
+    assert_escrow_invariant(&env, &client, &token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_withdraw_for_respects_opt_out() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token,
+        &10000_i128,
+        &1000_u64,
+        &2000_u64,
+    );
+
+    client.set_push_opt_out(&recipient, &true);
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.withdraw_for(&Address::generate(&env), &stream_id);
+}
+
+// ── Batch claims ─────────────────────────────────────────────────
+
+#[test]
+fn test_claim_all_across_clients() {
+    let (env, admin, client) = setup_env();
+    let client_a = Address::generate(&env);
+    let client_b = Address::generate(&env);
+    let contractor = Address::generate(&env);
+    let usdc = create_funded_token(&env, &client_a, 20000);
+    let xlm = create_funded_token(&env, &client_b, 10000);
+    let usdc_client = create_token_client(&env, &usdc);
+    let xlm_client = create_token_client(&env, &xlm);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let a1 = client.create_stream(&client_a, &contractor, &usdc, &10000_i128, &1000, &2000);
+    let a2 = client.create_stream(&client_a, &contractor, &usdc, &10000_i128, &1000, &1200);
+    let b1 = client.create_stream(&client_b, &contractor, &xlm, &10000_i128, &1000, &2000);
+
+    // Finish and claim the short stream so it is skipped later
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.claim(&contractor, &a2);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    let claims = client.claim_all(&contractor, &None, &0).claims;
+    assert_eq!(claims.len(), 2);
+    assert_eq!(
+        claims.get(0).unwrap(),
+        StreamClaim {
+            stream_id: a1,
+            token: usdc.clone(),
+            amount: 5000,
+        }
+    );
+    assert_eq!(claims.get(1).unwrap().stream_id, b1);
+    assert_eq!(usdc_client.balance(&contractor), 15000);
+    assert_eq!(xlm_client.balance(&contractor), 5000);
+
+    // Filtering by token only touches matching streams
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1600;
+    });
+    let claims = client.claim_all(&contractor, &Some(xlm.clone()), &0).claims;
+    assert_eq!(claims.len(), 1);
+    assert_eq!(xlm_client.balance(&contractor), 6000);
+    assert_eq!(client.get_claimable(&a1), 1000);
+}
+
+// ── Batch cancellation ───────────────────────────────────────────
+
+#[test]
+fn test_cancel_batch_settles_and_aggregates_refunds() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 30000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let a = client.create_stream(&sender, &alice, &token, &10000_i128, &1000, &2000);
+    let b = client.create_stream(&sender, &bob, &token, &20000_i128, &1000, &3000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    let report = client.cancel_batch(&sender, &Vec::from_array(&env, [a, b]));
+
+    assert_eq!(report.settlements.len(), 2);
+    assert_eq!(
+        report.settlements.get(1).unwrap(),
+        StreamSettlement {
+            stream_id: b,
+            recipient: bob.clone(),
+            token: token.clone(),
+            paid: 5000,
+            refunded: 15000,
+        }
+    );
+    assert_eq!(report.refunds.get(token.clone()).unwrap(), 20000);
+    assert_eq!(token_client.balance(&alice), 5000);
+    assert_eq!(token_client.balance(&bob), 5000);
+    assert_eq!(token_client.balance(&sender), 20000);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_cancel_all_for_recipient_offboards() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let other_org = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+    let other_token = create_funded_token(&env, &other_org, 10000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    client.create_stream(&sender, &employee, &token, &10000_i128, &1000, &2000);
+    client.create_stream(&sender, &employee, &token, &10000_i128, &1000, &2000);
+    let kept = client.create_stream(&other_org, &employee, &other_token, &10000_i128, &1000, &2000);
+
+    let report = client.cancel_all_for_recipient(&sender, &employee, &0);
+    assert_eq!(report.settlements.len(), 2);
+    assert_eq!(report.refunds.get(token.clone()).unwrap(), 20000);
+    assert_eq!(client.get_stream(&kept).status, StreamStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_cancel_batch_is_atomic() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+
+    let a = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    let b = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.cancel_stream(&sender, &b);
+
+    client.cancel_batch(&sender, &Vec::from_array(&env, [a, b]));
+}
+
+// ── Payout splits ────────────────────────────────────────────────
+
+#[test]
+fn test_claim_divides_payout_among_splits() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let savings = Address::generate(&env);
+    let tax = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &4000);
+
+    client.set_withholding(
+        &sender,
+        &stream_id,
+        &Some(PayoutSplit {
+            destination: tax.clone(),
+            bps: 1000,
+        }),
+    );
+    client.set_splits(
+        &recipient,
+        &stream_id,
+        &Vec::from_array(
+            &env,
+            [PayoutSplit {
+                destination: savings.clone(),
+                bps: 2000,
+            }],
+        ),
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 3333);
+
+    // Shares round down; the remainder goes to the recipient
+    assert_eq!(token_client.balance(&savings), 666);
+    assert_eq!(token_client.balance(&tax), 333);
+    assert_eq!(token_client.balance(&recipient), 2334);
+}
+
+#[test]
+fn test_withholding_applies_only_to_later_accruals() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let tax = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.set_withholding(
+        &sender,
+        &stream_id,
+        &Some(PayoutSplit {
+            destination: tax.clone(),
+            bps: 5000,
+        }),
+    );
+    assert_eq!(token_client.balance(&recipient), 5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    client.claim(&recipient, &stream_id);
+    assert_eq!(token_client.balance(&recipient), 7500);
+    assert_eq!(token_client.balance(&tax), 2500);
+}
+
+#[test]
+fn test_withholding_change_respects_push_opt_out() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let tax = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_push_opt_out(&recipient, &true);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    let split = Some(PayoutSplit {
+        destination: tax.clone(),
+        bps: 2000,
+    });
+    let result = client.try_set_withholding(&sender, &stream_id, &split);
+    assert_eq!(result, Err(Ok(StreamError::PushOptedOut)));
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    // Once the recipient has claimed, the new share applies to what accrues next
+    client.claim(&recipient, &stream_id);
+    client.set_withholding(&sender, &stream_id, &split);
+    assert_eq!(token_client.balance(&recipient), 5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    client.claim(&recipient, &stream_id);
+    assert_eq!(token_client.balance(&recipient), 9000);
+    assert_eq!(token_client.balance(&tax), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_withholding_above_cap_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_withholding(
+        &sender,
+        &stream_id,
+        &Some(PayoutSplit {
+            destination: Address::generate(&env),
+            bps: 5001,
+        }),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_withholding_to_sender_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_withholding(
+        &sender,
+        &stream_id,
+        &Some(PayoutSplit {
+            destination: sender.clone(),
+            bps: 1000,
+        }),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_splits_cannot_exceed_withholding_headroom() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_withholding(
+        &sender,
+        &stream_id,
+        &Some(PayoutSplit {
+            destination: Address::generate(&env),
+            bps: 3000,
+        }),
+    );
+
+    client.set_splits(
+        &recipient,
+        &stream_id,
+        &Vec::from_array(
+            &env,
+            [PayoutSplit {
+                destination: Address::generate(&env),
+                bps: 7001,
+            }],
+        ),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_split_count_is_capped() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    let mut splits = Vec::new(&env);
+    for _ in 0..6 {
+        splits.push_back(PayoutSplit {
+            destination: Address::generate(&env),
+            bps: 1,
+        });
+    }
+    client.set_splits(&recipient, &stream_id, &splits);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_only_recipient_sets_splits() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_splits(
+        &sender,
+        &stream_id,
+        &Vec::from_array(
+            &env,
+            [PayoutSplit {
+                destination: sender.clone(),
+                bps: 5000,
+            }],
+        ),
+    );
+}
+
+// ── Cancel policies ──────────────────────────────────────────────
+
+fn policy_params(recipient: &Address, token: &Address, policy: CancelPolicy) -> CreateStreamParams {
+    CreateStreamParams {
+        recipient: recipient.clone(),
+        token: token.clone(),
+        total_amount: 10000,
+        start_time: 1000,
+        end_time: 2000,
+        cliff_time: 0,
+        cancel_policy: policy,
+        arbiter: None,
+    }
+}
+
+#[test]
+fn test_notice_period_keeps_accruing_after_cancel_request() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Notice(200));
+    let stream_id = client.create_stream_with_params(&sender, &params);
+    assert_eq!(client.get_stream(&stream_id).cancel_policy, CancelPolicy::Notice(200));
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1300;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.status, StreamStatus::Active);
+    assert_eq!(stream.cancel_at, 1500);
+
+    // Accrual continues through the notice period and stops when it ends
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1400;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 4000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1800;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 5000);
+
+    client.cancel_stream(&sender, &stream_id);
+    assert_eq!(client.get_stream(&stream_id).status, StreamStatus::Cancelled);
+    assert_eq!(token_client.balance(&recipient), 5000);
+    assert_eq!(token_client.balance(&sender), 5000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_cannot_settle_before_notice_period_ends() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Notice(200));
+    let stream_id = client.create_stream_with_params(&sender, &params);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1300;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1499;
+    });
+    client.cancel_stream(&sender, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_non_cancelable_stream_cannot_be_cancelled() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::NonCancelable);
+    let stream_id = client.create_stream_with_params(&sender, &params);
+    client.cancel_stream(&sender, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_non_cancelable_stream_cannot_be_paused() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::NonCancelable);
+    let stream_id = client.create_stream_with_params(&sender, &params);
+    client.pause_stream(&sender, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_non_cancelable_stream_rate_cannot_be_cut() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::NonCancelable);
+    let stream_id = client.create_stream_with_params(&sender, &params);
+    client.update_rate(&sender, &stream_id, &1, &1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_non_cancelable_stream_withholding_cannot_be_set() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::NonCancelable);
+    let stream_id = client.create_stream_with_params(&sender, &params);
+    client.set_withholding(
+        &sender,
+        &stream_id,
+        &Some(PayoutSplit {
+            destination: Address::generate(&env),
+            bps: 1000,
+        }),
+    );
+}
+
+#[test]
+fn test_withholding_can_be_lowered_during_notice_period() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let tax = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Notice(500));
+    let stream_id = client.create_stream_with_params(&sender, &params);
+    let split = |bps| {
+        Some(PayoutSplit {
+            destination: tax.clone(),
+            bps,
+        })
+    };
+    client.set_withholding(&sender, &stream_id, &split(2000));
+    client.cancel_stream(&sender, &stream_id);
+
+    let raised = client.try_set_withholding(&sender, &stream_id, &split(3000));
+    assert_eq!(raised, Err(Ok(StreamError::NoticePeriodActive)));
+    client.set_withholding(&sender, &stream_id, &split(1000));
+    assert_eq!(client.get_stream(&stream_id).withholding.get(0).unwrap().bps, 1000);
+    client.set_withholding(&sender, &stream_id, &None);
+    assert!(client.get_stream(&stream_id).withholding.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_rate_cannot_be_cut_during_notice_period() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Notice(200));
+    let stream_id = client.create_stream_with_params(&sender, &params);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1300;
+    });
+    client.cancel_stream(&sender, &stream_id);
+    client.update_rate(&sender, &stream_id, &1, &1300);
+}
+
+#[test]
+fn test_offboarding_skips_non_cancelable_streams() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+
+    let locked = client.create_stream_with_params(
+        &sender,
+        &policy_params(&recipient, &token, CancelPolicy::NonCancelable),
+    );
+    let open = client.create_stream_with_params(
+        &sender,
+        &policy_params(&recipient, &token, CancelPolicy::Cancelable),
+    );
+
+    let report = client.cancel_all_for_recipient(&sender, &recipient, &0);
+    assert_eq!(report.settlements.len(), 1);
+    assert_eq!(client.get_stream(&locked).status, StreamStatus::Active);
+    assert_eq!(client.get_stream(&open).status, StreamStatus::Cancelled);
+}
+
+// ── Operators ────────────────────────────────────────────────────
+
+/// Initialize with a funded org admin that has approved the contract to pull escrow.
+fn setup_org(env: &Env, client: &PayrollStreamContractClient, admin: &Address) -> Address {
+    let token = create_funded_token(env, admin, 20000);
+    client.initialize(admin);
+    create_token_client(env, &token).approve(admin, &client.address, &20000, &1000);
+    token
+}
+
+#[test]
+fn test_operator_runs_payroll_for_org() {
+    let (env, admin, client) = setup_env();
+    let operator = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = setup_org(&env, &client, &admin);
+    let token_client = create_token_client(&env, &token);
+
+    client.grant_operator(&admin, &operator, &token, &15000);
+    assert!(client.is_operator(&operator));
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Cancelable);
+    let stream_id = client.create_stream_as_operator(&operator, &params);
+
+    // The org funds and owns the stream; the operator is recorded as its creator
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.sender, admin);
+    assert_eq!(stream.operator, operator);
+    assert_eq!(token_client.balance(&admin), 10000);
+    assert_eq!(client.get_streams_by_sender(&admin).len(), 1);
+    assert_eq!(client.get_operator_budget(&operator, &token).spent, 10000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.pause_stream(&operator, &stream_id);
+    client.resume_stream(&operator, &stream_id);
+    client.cancel_stream(&operator, &stream_id);
+
+    // Refund goes back to the org, not the operator
+    assert_eq!(token_client.balance(&recipient), 5000);
+    assert_eq!(token_client.balance(&admin), 15000);
+    assert_eq!(token_client.balance(&operator), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_operator_cannot_exceed_budget() {
+    let (env, admin, client) = setup_env();
+    let operator = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = setup_org(&env, &client, &admin);
+
+    client.grant_operator(&admin, &operator, &token, &15000);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Cancelable);
+    client.create_stream_as_operator(&operator, &params);
+    client.create_stream_as_operator(&operator, &params);
+}
+
+#[test]
+fn test_operator_budget_covers_creation_fee() {
+    let (env, admin, client) = setup_env();
+    let operator = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = setup_org(&env, &client, &admin);
+
+    client.set_fee_config(
+        &admin,
+        &FeeConfig {
+            mode: FeeMode::Proportional(100),
+            timing: FeeTiming::OnCreate,
+            collector: admin.clone(),
+        },
+    );
+    client.grant_operator(&admin, &operator, &token, &10000);
+
+    // 10000 of escrow plus a 100 fee does not fit in a budget of 10000
+    let params = policy_params(&recipient, &token, CancelPolicy::Cancelable);
+    let result = client.try_create_stream_as_operator(&operator, &params);
+    assert_eq!(result, Err(Ok(StreamError::BudgetExceeded)));
+
+    client.grant_operator(&admin, &operator, &token, &10100);
+    client.create_stream_as_operator(&operator, &params);
+    assert_eq!(client.get_operator_budget(&operator, &token).spent, 10100);
+}
+
+#[test]
+fn test_operator_batch_cancels_org_streams() {
+    let (env, admin, client) = setup_env();
+    let operator = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = setup_org(&env, &client, &admin);
+    let token_client = create_token_client(&env, &token);
+
+    client.grant_operator(&admin, &operator, &token, &20000);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Cancelable);
+    let first = client.create_stream_as_operator(&operator, &params);
+    client.create_stream_as_operator(&operator, &params);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    let report = client.cancel_batch(&operator, &Vec::from_array(&env, [first]));
+    assert_eq!(report.settlements.len(), 1);
+
+    let report = client.cancel_all_for_recipient(&operator, &recipient, &0);
+    assert_eq!(report.settlements.len(), 1);
+
+    // Refunds go back to the org, never to the operator
+    assert_eq!(token_client.balance(&recipient), 10000);
+    assert_eq!(token_client.balance(&admin), 10000);
+    assert_eq!(token_client.balance(&operator), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_revoked_operator_cannot_manage_org_streams() {
+    let (env, admin, client) = setup_env();
+    let operator = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = setup_org(&env, &client, &admin);
+
+    client.grant_operator(&admin, &operator, &token, &15000);
+
+    let params = policy_params(&recipient, &token, CancelPolicy::Cancelable);
+    let stream_id = client.create_stream_as_operator(&operator, &params);
+
+    client.revoke_operator(&admin, &operator);
+    assert!(!client.is_operator(&operator));
+    client.pause_stream(&operator, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_only_admin_grants_operators() {
+    let (env, admin, client) = setup_env();
+    let outsider = Address::generate(&env);
+    let token = setup_org(&env, &client, &admin);
+
+    client.grant_operator(&outsider, &outsider, &token, &15000);
+}
+
+// ── Stream listings ──────────────────────────────────────────────
+
+#[test]
+fn test_list_sender_streams_pages_across_index_chunks() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 12000);
+
+    client.initialize(&admin);
+
+    for _ in 0..120 {
+        client.create_stream(&sender, &recipient, &token, &100_i128, &1000, &2000);
+    }
+
+    let mut ids: Vec<u32> = Vec::new(&env);
+    let mut cursor = 0;
+    let mut pages = 0;
+    loop {
+        let page = client.list_sender_streams(&sender, &cursor, &50, &None, &None);
+        for summary in page.streams.iter() {
+            ids.push_back(summary.id);
+        }
+        pages += 1;
+        match page.next_cursor {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, 3);
+    assert_eq!(ids.len(), 120);
+    for (position, id) in ids.iter().enumerate() {
+        assert_eq!(id, position as u32);
+    }
+    assert_eq!(client.get_streams_by_sender(&sender).len(), 100);
+}
+
+#[test]
+fn test_list_recipient_streams_filters_by_status_and_token() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let usdc = create_funded_token(&env, &sender, 20000);
+    let eurc = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let first = client.create_stream(&sender, &recipient, &usdc, &10000_i128, &1000, &2000);
+    let second = client.create_stream(&sender, &recipient, &usdc, &10000_i128, &1000, &2000);
+    client.create_stream(&sender, &recipient, &eurc, &10000_i128, &1000, &2000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1250;
+    });
+    client.cancel_stream(&sender, &second);
+
+    let page = client.list_recipient_streams(&recipient, &0, &10, &None, &Some(usdc.clone()));
+    assert_eq!(page.streams.len(), 2);
+    assert_eq!(page.next_cursor, None);
+
+    let active = client.list_recipient_streams(
+        &recipient,
+        &0,
+        &10,
+        &Some(StreamStatus::Active),
+        &Some(usdc.clone()),
+    );
+    assert_eq!(active.streams.len(), 1);
+    let summary = active.streams.get(0).unwrap();
+    assert_eq!(summary.id, first);
+    assert_eq!(summary.claimable, 2500);
+
+    let cancelled =
+        client.list_recipient_streams(&recipient, &0, &10, &Some(StreamStatus::Cancelled), &None);
+    assert_eq!(cancelled.streams.len(), 1);
+    assert_eq!(cancelled.streams.get(0).unwrap().claimable, 0);
+}
+
+#[test]
+fn test_listing_cursor_survives_transfer() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 30000);
+
+    client.initialize(&admin);
+
+    let first = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    let second = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    let third = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    let page = client.list_recipient_streams(&recipient, &0, &2, &None, &None);
+    assert_eq!(page.streams.get(1).unwrap().id, second);
+
+    // Moving an already listed stream away must not shift the rest of the index
+    client.transfer_stream(&recipient, &first, &Address::generate(&env));
+    let cursor = page.next_cursor.unwrap();
+    let page = client.list_recipient_streams(&recipient, &cursor, &2, &None, &None);
+    assert_eq!(page.streams.len(), 1);
+    assert_eq!(page.streams.get(0).unwrap().id, third);
+    assert_eq!(client.get_streams_by_recipient(&recipient).len(), 2);
+}
+
+#[test]
+fn test_listing_with_zero_limit_still_advances() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+
+    let first = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    let second = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    let page = client.list_sender_streams(&sender, &0, &0, &None, &None);
+    assert_eq!(page.streams.get(0).unwrap().id, first);
+    assert_eq!(page.next_cursor, Some(1));
+
+    let page = client.list_sender_streams(&sender, &1, &0, &None, &None);
+    assert_eq!(page.streams.get(0).unwrap().id, second);
+    assert_eq!(page.next_cursor, None);
+}
+
+// ── Storage lifetime ─────────────────────────────────────────────
+
+fn stream_ttl(env: &Env, client: &PayrollStreamContractClient, stream_id: u32) -> u32 {
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&storage::DataKey::Stream(stream_id))
+    })
+}
+
+#[test]
+fn test_bump_stream_extends_ttl() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    assert_eq!(stream_ttl(&env, &client, stream_id), storage::PERSISTENT_BUMP_AMOUNT);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 2 * storage::DAY_IN_LEDGERS;
+    });
+    assert_eq!(
+        stream_ttl(&env, &client, stream_id),
+        storage::PERSISTENT_BUMP_AMOUNT - 2 * storage::DAY_IN_LEDGERS
+    );
+
+    client.bump_stream(&stream_id);
+    assert_eq!(stream_ttl(&env, &client, stream_id), storage::PERSISTENT_BUMP_AMOUNT);
+}
+
+#[test]
+fn test_archive_and_restore_finished_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.cancel_stream(&sender, &stream_id);
+    let record = client.get_stream(&stream_id);
+
+    client.archive_stream(&sender, &stream_id);
+    assert_eq!(
+        client.try_get_stream(&stream_id),
+        Err(Ok(StreamError::StreamArchived))
+    );
+
+    // Archived streams drop out of listings without breaking them
+    let page = client.list_sender_streams(&sender, &0, &10, &None, &None);
+    assert_eq!(page.streams.len(), 0);
+
+    client.restore_stream(&record);
+    assert_eq!(client.get_stream(&stream_id), record);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")]
+fn test_cannot_archive_live_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.archive_stream(&sender, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_only_parties_archive_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.cancel_stream(&sender, &stream_id);
+    client.archive_stream(&Address::generate(&env), &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_restore_rejects_tampered_record() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.cancel_stream(&sender, &stream_id);
+    let mut record = client.get_stream(&stream_id);
+    client.archive_stream(&sender, &stream_id);
+
+    record.total_amount += 1;
+    client.restore_stream(&record);
+}
+
+// ── Milestone streams ────────────────────────────────────────────
+
+fn milestone_params(env: &Env) -> Vec<MilestoneParams> {
+    Vec::from_array(
+        env,
+        [
+            MilestoneParams {
+                amount: 3000,
+                description_hash: BytesN::from_array(env, &[1; 32]),
+                due_date: 2000,
+            },
+            MilestoneParams {
+                amount: 7000,
+                description_hash: BytesN::from_array(env, &[2; 32]),
+                due_date: 3000,
+            },
+        ],
+    )
+}
+
+fn milestone_terms(reviewer: &Address, on_timeout: MilestoneTimeout) -> MilestoneTerms {
+    MilestoneTerms {
+        reviewer: reviewer.clone(),
+        review_period: 500,
+        on_timeout,
+    }
+}
+
+#[test]
+fn test_approved_milestones_release_to_recipient() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRefund),
+    );
+    assert_eq!(token_client.balance(&sender), 0);
+    assert_eq!(client.get_claimable(&stream_id), 0);
+
+    client.approve_milestone(&reviewer, &stream_id, &0);
+    assert_eq!(token_client.balance(&recipient), 3000);
+
+    client.approve_milestone(&sender, &stream_id, &1);
+    assert_eq!(token_client.balance(&recipient), 10000);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.status, StreamStatus::Completed);
+    let StreamKind::Milestone(_, milestones) = stream.kind else {
+        panic!("expected a milestone stream");
+    };
+    assert_eq!(milestones.get(1).unwrap().status, MilestoneStatus::Released);
+}
+
+#[test]
+fn test_unreviewed_milestones_settle_by_timeout_policy() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let released = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRelease),
+    );
+    let refunded = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRefund),
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2500;
+    });
+    client.settle_milestone(&released, &0);
+    client.settle_milestone(&refunded, &0);
+
+    assert_eq!(token_client.balance(&recipient), 3000);
+    assert_eq!(token_client.balance(&sender), 3000);
+    assert_eq!(client.get_stream(&refunded).total_amount, 7000);
+    assert_eq!(client.get_stream(&refunded).status, StreamStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn test_milestone_cannot_settle_during_review_period() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRelease),
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2499;
+    });
+    client.settle_milestone(&stream_id, &0);
+}
+
+#[test]
+fn test_disputed_milestone_waits_for_reviewer() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRelease),
+    );
+    client.dispute_milestone(&sender, &stream_id, &0);
+
+    // A disputed milestone does not settle when its review period runs out
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2500;
+    });
+    assert_eq!(
+        client.try_settle_milestone(&stream_id, &0),
+        Err(Ok(StreamError::MilestoneNotDue))
+    );
+
+    client.reject_milestone(&reviewer, &stream_id, &0);
+    assert_eq!(token_client.balance(&sender), 3000);
+    assert_eq!(token_client.balance(&recipient), 0);
+}
+
+#[test]
+fn test_disputed_milestone_falls_back_to_timeout_policy() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRelease),
+    );
+    client.dispute_milestone(&sender, &stream_id, &0);
+
+    // The reviewer never decides, so the dispute eventually settles like any timeout
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2500 + 30 * 24 * 60 * 60;
+    });
+    client.settle_milestone(&stream_id, &0);
+    assert_eq!(token_client.balance(&recipient), 3000);
+}
+
+#[test]
+fn test_cancel_releases_timed_out_milestones() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRelease),
+    );
+
+    // The first milestone's review has run out, so cancelling cannot refund it
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2500;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    assert_eq!(token_client.balance(&recipient), 3000);
+    assert_eq!(token_client.balance(&sender), 7000);
+    let StreamKind::Milestone(_, milestones) = client.get_stream(&stream_id).kind else {
+        panic!("expected a milestone stream");
+    };
+    assert_eq!(milestones.get(0).unwrap().status, MilestoneStatus::Released);
+    assert_eq!(milestones.get(1).unwrap().status, MilestoneStatus::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_sender_cannot_reject_milestone() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_milestone_stream(
+        &sender,
+        &recipient,
+        &token,
+        &milestone_params(&env),
+        &milestone_terms(&reviewer, MilestoneTimeout::AutoRelease),
+    );
+    client.reject_milestone(&sender, &stream_id, &0);
+}
+
+// ── Salary advances ──────────────────────────────────────────────
+
+#[test]
+fn test_advance_is_repaid_by_later_accruals() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_advance_limit(&sender, &stream_id, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.claim(&recipient, &stream_id);
+
+    // Half of the 8000 still unstreamed may be advanced
+    client.request_advance(&recipient, &stream_id, &4000);
+    assert_eq!(token_client.balance(&recipient), 6000);
+    assert_eq!(client.get_stream(&stream_id).advance_outstanding, 4000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1400;
+    });
+    assert_eq!(client.get_claimable(&stream_id), 0);
+    assert_eq!(client.get_advance_debt(&stream_id), 2000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1700;
+    });
+    assert_eq!(client.get_advance_debt(&stream_id), 0);
+    assert_eq!(client.claim(&recipient, &stream_id), 1000);
+    assert_eq!(client.get_stream(&stream_id).advance_outstanding, 0);
+}
+
+#[test]
+fn test_cancel_settles_outstanding_advance() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_advance_limit(&sender, &stream_id, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.request_advance(&recipient, &stream_id, &6000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    // The recipient earned 5000 but was paid 6000; only the rest of escrow is refunded
+    assert_eq!(token_client.balance(&recipient), 6000);
+    assert_eq!(token_client.balance(&sender), 4000);
+    assert_eq!(client.get_stream(&stream_id).advance_outstanding, 1000);
+    assert_eq!(client.get_advance_debt(&stream_id), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn test_advance_capped_by_limit() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_advance_limit(&sender, &stream_id, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.claim(&recipient, &stream_id);
+    client.request_advance(&recipient, &stream_id, &4001);
+}
+
+#[test]
+fn test_top_up_and_renewal_allowed_while_advance_outstanding() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_advance_limit(&sender, &stream_id, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1200;
+    });
+    client.request_advance(&recipient, &stream_id, &4000);
+
+    // Adding funds returns nothing to the sender, so the advance does not stand in the way
+    client.top_up_stream(&sender, &stream_id, &10000, &3000);
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Allowance);
+    assert_eq!(client.get_stream(&stream_id).total_amount, 20000);
+    assert_eq!(client.get_advance_debt(&stream_id), 2000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 16000);
+    assert_eq!(token_client.balance(&recipient), 20000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #35)")]
+fn test_rate_cut_blocked_while_advance_outstanding() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+
+    let stream_a = client.create_stream(
+        &sender, &Address::generate(&env), &token, &10000_i128, &1000, &2000,
+    );
+    client.create_stream(&sender, &Address::generate(&env), &token, &10000_i128, &1000, &2000);
+    client.set_advance_limit(&sender, &stream_a, &5000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1100;
+    });
+    client.request_advance(&client.get_stream(&stream_a).recipient, &stream_a, &5000);
+
+    // Refunding the unstreamed escrow would take tokens belonging to the other stream
+    client.update_rate(&sender, &stream_a, &1, &1100);
+}
+
+// ── Swapped payouts ──────────────────────────────────────────────
+
+mod mock_router {
+    use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env};
+
+    /// Minimal router used in tests — swaps at a fixed price with 7 decimals,
+    /// paying out of its own balance of the output token.
+    #[contract]
+    pub struct MockRouter;
+
+    #[contractimpl]
+    impl MockRouter {
+        pub fn set_price(env: Env, price: i128) {
+            env.storage().instance().set(&symbol_short!("price"), &price);
+        }
+
+        pub fn swap_exact_in(
+            env: Env,
+            from: Address,
+            token_in: Address,
+            token_out: Address,
+            amount_in: i128,
+            min_out: i128,
+            to: Address,
+        ) -> i128 {
+            let price: i128 = env.storage().instance().get(&symbol_short!("price")).unwrap();
+            let amount_out = amount_in * price / 10_000_000;
+            if amount_out < min_out {
+                panic!("insufficient output amount");
+            }
+
+            let router = env.current_contract_address();
+            token::Client::new(&env, &token_in).transfer_from(&router, &from, &router, &amount_in);
+            token::Client::new(&env, &token_out).transfer(&router, &to, &amount_out);
+            amount_out
+        }
+    }
+}
+
+use mock_router::{MockRouter, MockRouterClient};
+
+/// Register a router quoting two payout tokens per stream token, holding `liquidity`
+/// of a fresh payout token. Returns the router and the payout token.
+fn setup_router(
+    env: &Env,
+    client: &PayrollStreamContractClient,
+    admin: &Address,
+    liquidity: i128,
+) -> (Address, Address) {
+    let router = env.register(MockRouter, ());
+    MockRouterClient::new(env, &router).set_price(&20_000_000);
+    client.set_swap_router(admin, &router);
+    (router.clone(), create_funded_token(env, &router, liquidity))
+}
+
+#[test]
+fn test_claim_pays_in_swapped_token() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+    let (router, xlm) = setup_router(&env, &client, &admin, 50000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_payout_token(&recipient, &stream_id, &Some(xlm.clone()), &19_000_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 5000);
+
+    assert_eq!(create_token_client(&env, &xlm).balance(&recipient), 10000);
+    assert_eq!(create_token_client(&env, &token).balance(&recipient), 0);
+    assert_eq!(create_token_client(&env, &token).balance(&router), 5000);
+}
+
+#[test]
+fn test_failed_swap_falls_back_to_stream_token() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    let (router, xlm) = setup_router(&env, &client, &admin, 50000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    // The recipient will not accept less than 2.5 XLM per unit; the router only offers 2
+    client.set_payout_token(&recipient, &stream_id, &Some(xlm.clone()), &25_000_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 5000);
+
+    assert_eq!(token_client.balance(&recipient), 5000);
+    assert_eq!(create_token_client(&env, &xlm).balance(&recipient), 0);
+    assert_eq!(token_client.allowance(&client.address, &router), 0);
+}
+
+#[test]
+fn test_cancel_pays_in_stream_token_despite_payout_token() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    let (_router, xlm) = setup_router(&env, &client, &admin, 50000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_payout_token(&recipient, &stream_id, &Some(xlm.clone()), &19_000_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    client.cancel_stream(&sender, &stream_id);
+
+    // Settlement never goes through the router, whatever the recipient configured
+    assert_eq!(token_client.balance(&recipient), 5000);
+    assert_eq!(token_client.balance(&sender), 5000);
+    assert_eq!(create_token_client(&env, &xlm).balance(&recipient), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_payout_min_rate_is_bounded() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+    let (_router, xlm) = setup_router(&env, &client, &admin, 50000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_payout_token(&recipient, &stream_id, &Some(xlm), &i128::MAX);
+}
+
+// ── Claim history ────────────────────────────────────────────────
+
+#[test]
+fn test_claim_history_records_each_payout() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let new_recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    for timestamp in [1200, 1500] {
+        env.ledger().with_mut(|li| {
+            li.timestamp = timestamp;
+        });
+        client.claim(&recipient, &stream_id);
+    }
+    client.transfer_stream(&recipient, &stream_id, &new_recipient);
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1800;
+    });
+    client.claim(&new_recipient, &stream_id);
+
+    let first = client.get_claim_history(&stream_id, &0, &2);
+    assert_eq!(
+        first.records,
+        Vec::from_array(
+            &env,
+            [
+                ClaimRecord {
+                    amount: 2000,
+                    timestamp: 1200,
+                    destination: recipient.clone(),
+                },
+                ClaimRecord {
+                    amount: 3000,
+                    timestamp: 1500,
+                    destination: recipient.clone(),
+                },
+            ],
+        )
+    );
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.get_claim_history(&stream_id, &2, &2);
+    assert_eq!(second.records.len(), 1);
+    let last = second.records.get(0).unwrap();
+    assert_eq!(last.amount, 3000);
+    assert_eq!(last.destination, new_recipient);
+    assert_eq!(second.next_cursor, None);
+
+    // A zero limit still returns a record, so following the cursor terminates
+    let single = client.get_claim_history(&stream_id, &0, &0);
+    assert_eq!(single.records.len(), 1);
+    assert_eq!(single.next_cursor, Some(1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_claim_history_unknown_stream() {
+    let (_env, admin, client) = setup_env();
+    client.initialize(&admin);
+    client.get_claim_history(&7, &0, &10);
+}
+
+// ── Fees ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_creation_fee_is_charged_on_top_of_escrow() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10100);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    client.set_fee_config(
+        &admin,
+        &FeeConfig {
+            mode: FeeMode::Proportional(100),
+            timing: FeeTiming::OnCreate,
+            collector: collector.clone(),
+        },
+    );
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    assert_eq!(token_client.balance(&sender), 0);
+    assert_eq!(client.get_stream(&stream_id).total_amount, 10000);
+    assert_eq!(client.get_accrued_fees(&token), 100);
+
+    assert_eq!(client.withdraw_fees(&collector, &token), 100);
+    assert_eq!(token_client.balance(&collector), 100);
+    assert_eq!(client.get_accrued_fees(&token), 0);
+}
+
+#[test]
+fn test_creation_fee_is_charged_on_added_escrow() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 1_000_000);
+
+    client.initialize(&admin);
+    client.set_fee_config(
+        &admin,
+        &FeeConfig {
+            mode: FeeMode::Proportional(100),
+            timing: FeeTiming::OnCreate,
+            collector: admin.clone(),
+        },
+    );
+
+    let stream_id = client.create_stream(&sender, &recipient, &token, &100_i128, &1000, &2000);
+    client.top_up_stream(&sender, &stream_id, &100_000, &2000);
+    assert_eq!(client.get_accrued_fees(&token), 1001);
+
+    let flow_id = client.create_flow_stream(&sender, &recipient, &token, &1, &1000, &10_000);
+    client.deposit_flow(&sender, &flow_id, &20_000);
+    assert_eq!(client.get_accrued_fees(&token), 1301);
+    assert_eq!(client.get_stream(&flow_id).total_amount, 30_000);
+
+    // Renewed periods pay the fee out of the same funding source
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Buffer);
+    client.deposit_renewal(&sender, &stream_id, &102_010);
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    assert_eq!(client.renew_stream(&stream_id), 1);
+    assert_eq!(client.get_accrued_fees(&token), 2302);
+    assert_eq!(client.get_stream(&stream_id).renewal.buffer, 909);
+}
+
+#[test]
+fn test_claim_fee_applies_only_to_streams_created_after_change() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let mut config = FeeConfig {
+        mode: FeeMode::Proportional(500),
+        timing: FeeTiming::OnClaim,
+        collector,
+    };
+    client.set_fee_config(&admin, &config);
+    let early = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    config.mode = FeeMode::Flat(700);
+    client.set_fee_config(&admin, &config);
+    let late = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    assert_eq!(client.get_stream(&early).claim_fee, FeeMode::Proportional(500));
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1500;
+    });
+    assert_eq!(client.claim(&recipient, &early), 5000);
+    assert_eq!(token_client.balance(&recipient), 4750);
+
+    client.claim(&recipient, &late);
+    assert_eq!(token_client.balance(&recipient), 9050);
+    assert_eq!(client.get_accrued_fees(&token), 950);
+}
+
+#[test]
+fn test_added_escrow_keeps_the_fee_terms_of_its_stream() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+
+    let mut config = FeeConfig {
+        mode: FeeMode::Proportional(100),
+        timing: FeeTiming::OnClaim,
+        collector: admin.clone(),
+    };
+    client.set_fee_config(&admin, &config);
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    config.mode = FeeMode::Proportional(1000);
+    config.timing = FeeTiming::OnCreate;
+    client.set_fee_config(&admin, &config);
+
+    // The top-up is charged under the stream's own terms, which have no creation fee
+    client.top_up_stream(&sender, &stream_id, &10000, &3000);
+    assert_eq!(token_client.balance(&sender), 0);
+    assert_eq!(client.get_accrued_fees(&token), 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    assert_eq!(client.claim(&recipient, &stream_id), 20000);
+    assert_eq!(client.get_accrued_fees(&token), 200);
+}
+
+#[test]
+fn test_flat_claim_fee_waived_on_pushed_payouts() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let bot = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    client.set_fee_config(
+        &admin,
+        &FeeConfig {
+            mode: FeeMode::Flat(100),
+            timing: FeeTiming::OnClaim,
+            collector: admin.clone(),
+        },
+    );
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    // A third party paying out every few seconds cannot eat the pay in flat fees
+    for timestamp in [1005, 1010, 1015, 1020] {
+        env.ledger().with_mut(|li| {
+            li.timestamp = timestamp;
+        });
+        client.withdraw_for(&bot, &stream_id);
+    }
+    assert_eq!(token_client.balance(&recipient), 200);
+    assert_eq!(client.get_accrued_fees(&token), 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1100;
+    });
+    client.withdraw_for(&recipient, &stream_id);
+    assert_eq!(token_client.balance(&recipient), 900);
+    assert_eq!(client.get_accrued_fees(&token), 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_only_collector_withdraws_fees() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10100);
+
+    client.initialize(&admin);
+    client.set_fee_config(
+        &admin,
+        &FeeConfig {
+            mode: FeeMode::Flat(100),
+            timing: FeeTiming::OnCreate,
+            collector: Address::generate(&env),
+        },
+    );
+    client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+
+    client.withdraw_fees(&sender, &token);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #29)")]
+fn test_fee_above_100_percent_rejected() {
+    let (_env, admin, client) = setup_env();
+    client.initialize(&admin);
+    client.set_fee_config(
+        &admin,
+        &FeeConfig {
+            mode: FeeMode::Proportional(10_001),
+            timing: FeeTiming::OnClaim,
+            collector: admin.clone(),
+        },
+    );
+}
+
+// ── Renewals ─────────────────────────────────────────────────────
+
+#[test]
+fn test_renewal_from_allowance() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 30000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Allowance);
+    token_client.approve(&sender, &client.address, &20000, &1000);
+
+    // Two periods have ended; both are started in one call
+    env.ledger().with_mut(|li| li.timestamp = 3500);
+    assert_eq!(client.renew_stream(&stream_id), 2);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.total_amount, 30000);
+    assert_eq!(stream.end_time, 4000);
+    assert_eq!(token_client.balance(&sender), 0);
+    assert_eq!(client.get_claimable(&stream_id), 25000);
+
+    // Claiming everything so far leaves the stream open for the next period
+    env.ledger().with_mut(|li| li.timestamp = 4000);
+    client.claim(&recipient, &stream_id);
+    assert_eq!(client.get_stream(&stream_id).status, StreamStatus::Active);
+
+    // Out of allowance: renewal pauses instead of failing
+    assert_eq!(client.renew_stream(&stream_id), 0);
+    assert!(client.get_stream(&stream_id).renewal.paused);
+}
+
+#[test]
+fn test_renewal_from_buffer() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 30000);
+
+    client.initialize(&admin);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Buffer);
+    client.deposit_renewal(&sender, &stream_id, &15000);
+
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    assert_eq!(client.renew_stream(&stream_id), 1);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.total_amount, 20000);
+    assert_eq!(stream.renewal.buffer, 5000);
+    assert!(stream.renewal.paused);
+
+    // Topping up the buffer resumes renewal
+    client.deposit_renewal(&sender, &stream_id, &5000);
+    assert_eq!(client.renew_stream(&stream_id), 1);
+    assert_eq!(client.get_stream(&stream_id).end_time, 4000);
+
+    // Switching funding source keeps the original period terms
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Allowance);
+    let renewal = client.get_stream(&stream_id).renewal;
+    assert_eq!(renewal.period_length, 1000);
+    assert_eq!(renewal.period_amount, 10000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_renewal_not_due() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Buffer);
+    client.deposit_renewal(&sender, &stream_id, &10000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    client.renew_stream(&stream_id);
+}
+
+#[test]
+fn test_cancel_refunds_renewal_buffer() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.set_renewal(&sender, &stream_id, &RenewalFunding::Buffer);
+    client.deposit_renewal(&sender, &stream_id, &10000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    client.cancel_stream(&sender, &stream_id);
+
+    assert_eq!(token_client.balance(&recipient), 5000);
+    assert_eq!(token_client.balance(&sender), 15000);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert_eq!(client.get_stream(&stream_id).renewal, Renewal::disabled());
+}
+
+// ── Dispute holds ────────────────────────────────────────────────
+
+fn create_arbitrated_stream(
+    env: &Env,
+    client: &PayrollStreamContractClient,
+    sender: &Address,
+    recipient: &Address,
+    token: &Address,
+    arbiter: &Address,
+) -> u32 {
+    let mut params = policy_params(recipient, token, CancelPolicy::Cancelable);
+    params.arbiter = Some(arbiter.clone());
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.create_stream_with_params(sender, &params)
+}
+
+#[test]
+fn test_hold_freezes_and_arbiter_splits_escrow() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+    let token_client = create_token_client(&env, &token);
+
+    client.initialize(&admin);
+    let stream_id =
+        create_arbitrated_stream(&env, &client, &sender, &recipient, &token, &arbiter);
+
+    env.ledger().with_mut(|li| li.timestamp = 1200);
+    client.claim(&recipient, &stream_id);
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    client.hold_stream(&sender, &stream_id);
+
+    // Accrual stops at the hold and nothing moves until the arbiter decides
+    env.ledger().with_mut(|li| li.timestamp = 1800);
+    assert_eq!(client.get_claimable(&stream_id), 3000);
+    assert!(client.try_claim(&recipient, &stream_id).is_err());
+    assert!(client.try_cancel_stream(&sender, &stream_id).is_err());
+
+    client.resolve_hold(&arbiter, &stream_id, &6000);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.status, StreamStatus::Cancelled);
+    assert_eq!(stream.claimed_amount, 8000);
+    assert_eq!(token_client.balance(&recipient), 8000);
+    assert_eq!(token_client.balance(&sender), 2000);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")]
+fn test_claim_on_held_stream_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+    let stream_id =
+        create_arbitrated_stream(&env, &client, &sender, &recipient, &token, &arbiter);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    client.hold_stream(&recipient, &stream_id);
+    client.claim(&recipient, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")]
+fn test_top_up_on_held_stream_rejected() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 20000);
+
+    client.initialize(&admin);
+    let stream_id =
+        create_arbitrated_stream(&env, &client, &sender, &recipient, &token, &arbiter);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    client.hold_stream(&recipient, &stream_id);
+    client.top_up_stream(&sender, &stream_id, &5000, &3000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")]
+fn test_hold_requires_arbiter() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let stream_id = client.create_stream(&sender, &recipient, &token, &10000_i128, &1000, &2000);
+    client.hold_stream(&sender, &stream_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_only_arbiter_resolves_hold() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+    let stream_id =
+        create_arbitrated_stream(&env, &client, &sender, &recipient, &token, &arbiter);
+
+    client.hold_stream(&sender, &stream_id);
+    client.resolve_hold(&sender, &stream_id, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")]
+fn test_stream_cannot_be_transferred_to_arbiter() {
+    let (env, admin, client) = setup_env();
+    let sender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let token = create_funded_token(&env, &sender, 10000);
+
+    client.initialize(&admin);
+    let stream_id =
+        create_arbitrated_stream(&env, &client, &sender, &recipient, &token, &arbiter);
+
+    client.transfer_stream(&recipient, &stream_id, &arbiter);
+}
@@ -1,4 +1,21 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Map, Vec};
+
+pub(crate) const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+pub(crate) const MAX_BPS: u32 = 10_000;
+/// Most payout splits a recipient may set on one stream.
+pub(crate) const MAX_SPLITS: u32 = 5;
+/// Largest share of each payout a sender may withhold.
+pub(crate) const MAX_WITHHOLDING_BPS: u32 = 5_000;
+/// Fixed-point scale of payout exchange rates: `PRICE_SCALE` means one-for-one.
+pub(crate) const PRICE_SCALE: i128 = 10_000_000;
+/// Highest minimum payout rate a recipient may ask for: a billion payout-token units per
+/// unit of the stream token.
+pub(crate) const MAX_PAYOUT_RATE: i128 = 1_000_000_000 * PRICE_SCALE;
+/// How long past its review period a disputed milestone waits for the reviewer before
+/// the stream's timeout policy settles it.
+pub(crate) const DISPUTE_TIMEOUT: u64 = 30 * DAY_IN_SECONDS;
+/// Most index entries a single listing call will examine.
+pub(crate) const MAX_PAGE_SIZE: u32 = 100;
 
 /// Represents the status of a payment stream.
 #[contracttype]
@@ -12,6 +29,120 @@ pub enum StreamStatus {
     Cancelled,
     /// All tokens have been fully distributed and claimed.
     Completed,
+    /// Frozen by a dispute: nothing can be claimed or refunded until the arbiter resolves it.
+    Held,
+}
+
+/// How a stream accrues value over time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreamKind {
+    /// A fixed `total_amount` streamed linearly across the stream's segments.
+    Linear,
+    /// An open-ended stream paying `rate_per_second` for as long as the deposited
+    /// balance (`total_amount`) lasts. Accrual beyond the balance is owed as debt.
+    Flow,
+    /// A fixed `total_amount` released in equal installments on each payday.
+    Tranche(PaySchedule),
+    /// Escrowed payments released per deliverable as each milestone is approved.
+    Milestone(MilestoneTerms, Vec<Milestone>),
+}
+
+/// Review and timeout rules for a milestone stream.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneTerms {
+    /// Who besides the sender may approve milestones; the only party who may reject them.
+    pub reviewer: Address,
+    /// Seconds after a milestone's due date before it can be settled by `on_timeout`.
+    pub review_period: u64,
+    /// What happens to a pending milestone once its review period has run out.
+    pub on_timeout: MilestoneTimeout,
+}
+
+/// How an unreviewed milestone is settled after its review period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MilestoneTimeout {
+    /// Pay the milestone to the recipient.
+    AutoRelease,
+    /// Return the milestone to the sender.
+    AutoRefund,
+}
+
+/// Lifecycle of a single milestone.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MilestoneStatus {
+    /// Awaiting review.
+    Pending,
+    /// Contested; waits for the reviewer, up to `DISPUTE_TIMEOUT` past the review period.
+    Disputed,
+    /// Paid to the recipient.
+    Released,
+    /// Returned to the sender.
+    Refunded,
+}
+
+/// One deliverable of a milestone stream.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub amount: i128,
+    /// Hash of the off-chain deliverable description.
+    pub description_hash: BytesN<32>,
+    pub due_date: u64,
+    pub status: MilestoneStatus,
+}
+
+/// Parameters for one milestone when creating a milestone stream.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneParams {
+    pub amount: i128,
+    pub description_hash: BytesN<32>,
+    pub due_date: u64,
+}
+
+/// Length of a pay period for tranche streams.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PayPeriod {
+    /// Every 7 days.
+    Weekly,
+    /// Every 14 days.
+    BiWeekly,
+    /// Every 30 days.
+    Monthly,
+    /// A custom interval in seconds.
+    Custom(u64),
+}
+
+impl PayPeriod {
+    /// Length of the period in seconds.
+    pub fn seconds(&self) -> u64 {
+        match self {
+            PayPeriod::Weekly => 7 * DAY_IN_SECONDS,
+            PayPeriod::BiWeekly => 14 * DAY_IN_SECONDS,
+            PayPeriod::Monthly => 30 * DAY_IN_SECONDS,
+            PayPeriod::Custom(seconds) => *seconds,
+        }
+    }
+}
+
+/// Payday schedule for a tranche stream.
+/// Paydays fall every `period` from the first payday; `end_time` is always the last payday
+/// and releases any rounding remainder.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaySchedule {
+    /// Length of each pay period.
+    pub period: PayPeriod,
+    /// Seconds from `start_time` to the first payday. Zero means one full period.
+    pub first_payday_offset: u64,
+    /// Whether cancellation pays the current period pro rata instead of only
+    /// the paydays already reached.
+    pub prorate_on_cancel: bool,
 }
 
 /// A payment stream definition.
@@ -41,6 +172,168 @@ pub struct PayrollStream {
     pub status: StreamStatus,
     /// Rate of tokens per second (total_amount / duration).
     pub rate_per_second: i128,
+    /// Timestamp the current pause began. Only meaningful while `Paused`.
+    pub paused_at: u64,
+    /// Total seconds the stream has spent paused, excluding any current pause.
+    pub paused_duration: u64,
+    /// Rate history. Each segment streams its amount linearly over its own period;
+    /// the segment amounts always sum to `total_amount`.
+    pub segments: Vec<StreamSegment>,
+    /// Whether the recipient may transfer their claim rights to another address.
+    pub transferable: bool,
+    /// How the stream accrues value.
+    pub kind: StreamKind,
+    /// Nothing is claimable before this time; zero means no cliff.
+    pub cliff_time: u64,
+    /// Recipient-chosen shares of each payout sent to other addresses.
+    pub splits: Vec<PayoutSplit>,
+    /// Sender-mandated share of each payout (e.g. tax withholding); at most one entry.
+    pub withholding: Vec<PayoutSplit>,
+    /// Whether and how the sender may stop the stream early.
+    pub cancel_policy: CancelPolicy,
+    /// Time a requested notice-period cancellation takes effect; zero if none requested.
+    pub cancel_at: u64,
+    /// Who created the stream: the sender itself, or an operator acting for the org.
+    pub operator: Address,
+    /// Cap on unrepaid advances, in basis points of the unstreamed remainder; zero disables.
+    pub advance_limit_bps: u32,
+    /// Advances paid beyond what had accrued as of the last settlement. After cancellation
+    /// this is the advance debt the recipient never earned.
+    pub advance_outstanding: i128,
+    /// Token the recipient wants to be paid in, swapped from `token` at payout.
+    pub payout_token: Option<Address>,
+    /// Least `payout_token` accepted per unit of `token`, scaled by `PRICE_SCALE`.
+    pub payout_min_rate: i128,
+    /// Fee charged on top of escrow added to the stream, fixed when the stream was created.
+    pub creation_fee: FeeMode,
+    /// Fee deducted from each payout, fixed when the stream was created.
+    pub claim_fee: FeeMode,
+    /// Automatic renewal of the stream's pay period.
+    pub renewal: Renewal,
+    /// Address both parties agreed at creation may resolve a dispute hold.
+    pub arbiter: Option<Address>,
+    /// Accrual a flow stream owed beyond its deposits as of the last settlement. After
+    /// cancellation this is the debt the sender never paid.
+    pub debt: i128,
+}
+
+/// Where the money for each renewed period comes from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RenewalFunding {
+    /// The stream does not renew.
+    None,
+    /// Pulled from the sender's token allowance to this contract.
+    Allowance,
+    /// Drawn from tokens the sender deposited in advance.
+    Buffer,
+}
+
+/// Terms for rolling a stream over into a new period when the current one ends.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Renewal {
+    pub funding: RenewalFunding,
+    /// Length of each renewed period, in seconds.
+    pub period_length: u64,
+    /// Amount streamed over each renewed period.
+    pub period_amount: i128,
+    /// Deposited tokens not yet used for a period; refunded on cancellation.
+    pub buffer: i128,
+    /// Set when a period could not be funded; cleared when the sender funds again.
+    pub paused: bool,
+}
+
+impl Renewal {
+    /// Renewal turned off.
+    pub fn disabled() -> Self {
+        Renewal {
+            funding: RenewalFunding::None,
+            period_length: 0,
+            period_amount: 0,
+            buffer: 0,
+            paused: false,
+        }
+    }
+}
+
+/// How a fee is sized.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeMode {
+    /// No fee.
+    None,
+    /// A fixed amount of the stream token per charge.
+    Flat(i128),
+    /// A share of the amount charged, in basis points.
+    Proportional(u32),
+}
+
+/// When a fee is charged.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeTiming {
+    /// Charged to the sender on top of the escrow when a stream is created.
+    OnCreate,
+    /// Deducted from every payout to the recipient.
+    OnClaim,
+}
+
+/// Fee charged on streams created while this configuration is in force.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub mode: FeeMode,
+    pub timing: FeeTiming,
+    /// Address allowed to withdraw accrued fees.
+    pub collector: Address,
+}
+
+/// How much an operator may escrow from the org account in one token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorBudget {
+    /// Maximum cumulative amount the operator may escrow.
+    pub limit: i128,
+    /// Amount the org has paid so far, escrow and creation fees, for streams the operator
+    /// created.
+    pub spent: i128,
+}
+
+/// Whether and how the sender may cancel a stream before it ends.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CancelPolicy {
+    /// The sender may cancel at any time; accrual stops immediately.
+    Cancelable,
+    /// The stream can neither be cancelled nor paused by the sender.
+    NonCancelable,
+    /// Cancellation takes effect this many seconds after it is requested, and accrual
+    /// continues until then. The stream cannot be paused.
+    Notice(u64),
+}
+
+/// A share of each payout routed to another address.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutSplit {
+    /// Address receiving this share.
+    pub destination: Address,
+    /// Share of each payout in basis points (1/100th of a percent).
+    pub bps: u32,
+}
+
+/// One constant-rate period of a stream.
+/// Times are on the stream clock, i.e. with paused periods removed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamSegment {
+    /// Time at which this segment begins accruing.
+    pub start_time: u64,
+    /// Time at which this segment is fully accrued.
+    pub end_time: u64,
+    /// Amount streamed over this segment.
+    pub amount: i128,
 }
 
 /// Summary view for listing streams without full details.
@@ -48,12 +341,54 @@ pub struct PayrollStream {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StreamSummary {
     pub id: u32,
+    pub sender: Address,
     pub recipient: Address,
+    pub token: Address,
     pub total_amount: i128,
     pub claimed_amount: i128,
+    /// Amount the recipient could claim at the time of the query.
+    pub claimable: i128,
     pub status: StreamStatus,
 }
 
+/// A single payout to a stream's recipient.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimRecord {
+    /// Gross amount paid out, before payout splits and withholding.
+    pub amount: i128,
+    pub timestamp: u64,
+    /// Recipient of the stream at the time of the payout.
+    pub destination: Address,
+}
+
+/// One page of a stream's claim history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimHistoryPage {
+    pub records: Vec<ClaimRecord>,
+    /// Cursor to pass to fetch the next page; `None` once the history is exhausted.
+    pub next_cursor: Option<u32>,
+}
+
+/// One page of a stream listing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamPage {
+    pub streams: Vec<StreamSummary>,
+    /// Cursor to pass to fetch the next page; `None` once the listing is exhausted.
+    pub next_cursor: Option<u32>,
+}
+
+/// One page of a batch claim.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimPage {
+    pub claims: Vec<StreamClaim>,
+    /// Cursor to pass to claim the next page; `None` once every stream has been visited.
+    pub next_cursor: Option<u32>,
+}
+
 /// Parameters for creating a single stream in a batch.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -63,4 +398,44 @@ pub struct CreateStreamParams {
     pub total_amount: i128,
     pub start_time: u64,
     pub end_time: u64,
+    /// Optional cliff; zero means none. Must fall between `start_time` and `end_time`.
+    pub cliff_time: u64,
+    /// Whether and how the sender may cancel the stream early.
+    pub cancel_policy: CancelPolicy,
+    /// Address that resolves disputes; setting one requires the recipient's approval too.
+    pub arbiter: Option<Address>,
+}
+
+/// One line of a batch claim: what was claimed from a single stream.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamClaim {
+    pub stream_id: u32,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// How a single stream was settled when it was cancelled.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamSettlement {
+    pub stream_id: u32,
+    pub recipient: Address,
+    pub token: Address,
+    /// Accrued amount paid to the recipient.
+    pub paid: i128,
+    /// Unaccrued amount returned to the sender.
+    pub refunded: i128,
+}
+
+/// Result of a batch cancellation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementReport {
+    /// Per-stream settlement, in the order the streams were cancelled.
+    pub settlements: Vec<StreamSettlement>,
+    /// Total refunded to the sender per token.
+    pub refunds: Map<Address, i128>,
+    /// Cursor to resume a paginated cancellation from; `None` when nothing is left.
+    pub next_cursor: Option<u32>,
 }
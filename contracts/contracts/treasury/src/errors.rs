@@ -45,4 +45,28 @@ pub enum TreasuryError {
     /// The withdrawal proposal has expired.
     /// Triggered when attempting to interact with a proposal past its validity period.
     ProposalExpired = 13,
+    /// No price oracle has been configured.
+    /// Triggered when a reference-unit valuation is needed before `set_oracle()` was called.
+    OracleNotSet = 14,
+    /// The oracle has no usable price for the token.
+    /// Triggered if the oracle returns no quote or a non-positive price.
+    PriceNotFound = 15,
+    /// The oracle price is older than the configured maximum age.
+    /// Triggered when valuing a token whose last quote is stale.
+    StalePrice = 16,
+    /// The withdrawal would exceed the spending budget for the current period.
+    /// Triggered on execution when the withdrawal value plus period spending exceeds the limit.
+    BudgetExceeded = 17,
+    /// The token is already tracked by the treasury.
+    /// Triggered when trying to add a token that is already in the tracked list.
+    TokenAlreadyTracked = 18,
+    /// The token is not tracked by the treasury.
+    /// Triggered when trying to remove a token that is not in the tracked list.
+    TokenNotTracked = 19,
+    /// The approval tier table is malformed.
+    /// Triggered if tier amounts are not positive and strictly increasing, or approvals decrease.
+    InvalidTiers = 20,
+    /// A valuation does not fit in an `i128`.
+    /// Triggered if the oracle reports too many decimals or `amount * price` overflows.
+    ValuationOverflow = 21,
 }
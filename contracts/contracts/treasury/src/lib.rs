@@ -2,16 +2,21 @@
 use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, Symbol, Vec};
 
 mod errors;
+pub mod oracle;
 mod storage;
 mod types;
 
 use errors::TreasuryError;
+use oracle::PriceOracleClient;
 use storage::{
-    get_admin, get_proposal_count, get_signers, get_threshold, get_withdrawal, has_admin,
-    set_admin, set_proposal_count, set_signers, set_threshold, set_withdrawal,
+    get_admin, get_budget, get_oracle, get_proposal_count, get_signers, get_threshold,
+    get_tiers, get_tokens, get_withdrawal, has_admin, set_admin, set_budget, set_oracle,
+    set_proposal_count, set_signers, set_threshold, set_tiers, set_tokens, set_withdrawal,
     extend_instance_ttl, extend_withdrawal_ttl,
 };
-use types::{TreasuryConfig, WithdrawalRequest, WithdrawalStatus};
+use types::{
+    ApprovalTier, Budget, OracleConfig, TreasuryConfig, WithdrawalRequest, WithdrawalStatus,
+};
 
 #[contract]
 pub struct TreasuryContract;
@@ -26,6 +31,76 @@ impl TreasuryContract {
         Ok(())
     }
 
+    /// Internal helper to ensure `admin` matches the stored admin.
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), TreasuryError> {
+        Self::require_initialized(env)?;
+        if *admin != get_admin(env) {
+            return Err(TreasuryError::Unauthorized);
+        }
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// Internal helper to value `amount` of `token` in the oracle's reference unit.
+    /// Fails if no oracle is configured, the token has no price, the price is stale, or the
+    /// value overflows.
+    fn value_of(env: &Env, token: &Address, amount: i128) -> Result<i128, TreasuryError> {
+        let config = get_oracle(env).ok_or(TreasuryError::OracleNotSet)?;
+        let client = PriceOracleClient::new(env, &config.oracle);
+
+        let quote = client
+            .lastprice(token)
+            .ok_or(TreasuryError::PriceNotFound)?;
+        if quote.price <= 0 {
+            return Err(TreasuryError::PriceNotFound);
+        }
+
+        let now = env.ledger().timestamp();
+        if now.saturating_sub(quote.timestamp) > config.max_price_age {
+            return Err(TreasuryError::StalePrice);
+        }
+
+        let scale = 10_i128
+            .checked_pow(client.decimals())
+            .ok_or(TreasuryError::ValuationOverflow)?;
+        let value = amount
+            .checked_mul(quote.price)
+            .ok_or(TreasuryError::ValuationOverflow)?;
+        Ok(value / scale)
+    }
+
+    /// Internal helper returning the approvals needed for a withdrawal of `amount` of `token`.
+    /// Uses the first tier covering the amount. Larger amounts need the global threshold or
+    /// the top tier's approvals, whichever is higher, so they never need fewer than a tier.
+    fn required_approvals(env: &Env, token: &Address, amount: i128) -> u32 {
+        let threshold = get_threshold(env);
+        if let Some(tiers) = get_tiers(env).get(token.clone()) {
+            for tier in tiers.iter() {
+                if amount <= tier.max_amount {
+                    return tier.approvals;
+                }
+            }
+            if let Some(top) = tiers.last() {
+                return threshold.max(top.approvals);
+            }
+        }
+        threshold
+    }
+
+    /// Internal helper returning the highest approval count any withdrawal can require.
+    /// The signer set must never shrink below this value.
+    fn max_required_approvals(env: &Env) -> u32 {
+        let mut max = get_threshold(env);
+        for (_, tiers) in get_tiers(env).iter() {
+            for tier in tiers.iter() {
+                if tier.approvals > max {
+                    max = tier.approvals;
+                }
+            }
+        }
+        max
+    }
+
     /// Initialize the treasury with an admin and initial set of signers.
     /// The threshold defines how many signers must approve a withdrawal.
     pub fn initialize(
@@ -114,6 +189,13 @@ impl TreasuryContract {
         let mut approvals = Vec::new(&env);
         approvals.push_back(proposer.clone());
 
+        // The proposer's approval alone may satisfy a low tier
+        let status = if approvals.len() >= Self::required_approvals(&env, &token, amount) {
+            WithdrawalStatus::Approved
+        } else {
+            WithdrawalStatus::Pending
+        };
+
         let request = WithdrawalRequest {
             id: proposal_id,
             proposer: proposer.clone(),
@@ -122,7 +204,7 @@ impl TreasuryContract {
             amount,
             memo,
             approvals,
-            status: WithdrawalStatus::Pending,
+            status,
             created_at: env.ledger().timestamp(),
         };
 
@@ -177,9 +259,9 @@ impl TreasuryContract {
 
         request.approvals.push_back(signer.clone());
 
-        // Check if threshold is met
-        let threshold = get_threshold(&env);
-        if request.approvals.len() >= threshold {
+        // Check if the threshold for this amount's tier is met
+        let required = Self::required_approvals(&env, &request.token, request.amount);
+        if request.approvals.len() >= required {
             request.status = WithdrawalStatus::Approved;
         }
 
@@ -218,6 +300,22 @@ impl TreasuryContract {
             return Err(TreasuryError::InsufficientBalance);
         }
 
+        if let Some(mut budget) = get_budget(&env) {
+            let value = Self::value_of(&env, &request.token, request.amount)?;
+            let now = env.ledger().timestamp();
+            if now >= budget.period_start.saturating_add(budget.period) {
+                budget.period_start = now;
+                budget.spent = 0;
+            }
+            // Spending that does not even fit in an i128 is certainly over the limit
+            budget.spent = budget
+                .spent
+                .checked_add(value)
+                .filter(|spent| *spent <= budget.limit)
+                .ok_or(TreasuryError::BudgetExceeded)?;
+            set_budget(&env, &budget);
+        }
+
         token_client.transfer(&contract_address, &request.recipient, &request.amount);
 
         request.status = WithdrawalStatus::Executed;
@@ -269,9 +367,9 @@ impl TreasuryContract {
         admin.require_auth();
 
         let signers = get_signers(&env);
-        let threshold = get_threshold(&env);
 
-        if signers.len() <= threshold {
+        // Every tier, and the global threshold, must stay reachable
+        if signers.len() <= Self::max_required_approvals(&env) {
             return Err(TreasuryError::InvalidThreshold);
         }
 
@@ -327,6 +425,144 @@ impl TreasuryContract {
         Ok(())
     }
 
+    /// Set the approval tier table for `token`. Restricted to admin.
+    /// Tiers must have strictly increasing `max_amount` and non-decreasing `approvals`,
+    /// each reachable by the current signer set. An empty table removes the token's tiers.
+    pub fn set_tiers(
+        env: Env,
+        admin: Address,
+        token: Address,
+        tiers: Vec<ApprovalTier>,
+    ) -> Result<(), TreasuryError> {
+        Self::require_admin(&env, &admin)?;
+
+        let signer_count = get_signers(&env).len();
+        let mut previous: Option<ApprovalTier> = None;
+        for tier in tiers.iter() {
+            if tier.approvals == 0 || tier.approvals > signer_count {
+                return Err(TreasuryError::InvalidThreshold);
+            }
+            if tier.max_amount <= 0 {
+                return Err(TreasuryError::InvalidTiers);
+            }
+            if let Some(prev) = previous {
+                if tier.max_amount <= prev.max_amount || tier.approvals < prev.approvals {
+                    return Err(TreasuryError::InvalidTiers);
+                }
+            }
+            previous = Some(tier);
+        }
+
+        let mut table = get_tiers(&env);
+        if tiers.is_empty() {
+            table.remove(token.clone());
+        } else {
+            table.set(token.clone(), tiers);
+        }
+        set_tiers(&env, &table);
+
+        extend_instance_ttl(&env);
+
+        env.events().publish((symbol_short!("tiers"),), token);
+
+        Ok(())
+    }
+
+    /// Configure the price oracle used to value treasury holdings. Restricted to admin.
+    /// Prices older than `max_price_age` seconds are rejected as stale.
+    pub fn set_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        max_price_age: u64,
+    ) -> Result<(), TreasuryError> {
+        Self::require_admin(&env, &admin)?;
+
+        set_oracle(
+            &env,
+            &OracleConfig {
+                oracle: oracle.clone(),
+                max_price_age,
+            },
+        );
+
+        extend_instance_ttl(&env);
+
+        env.events().publish((symbol_short!("oracle"),), oracle);
+
+        Ok(())
+    }
+
+    /// Add a token to the set counted by `get_total_value`. Restricted to admin.
+    pub fn add_token(env: Env, admin: Address, token: Address) -> Result<(), TreasuryError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut tokens = get_tokens(&env);
+        if tokens.contains(&token) {
+            return Err(TreasuryError::TokenAlreadyTracked);
+        }
+        tokens.push_back(token.clone());
+        set_tokens(&env, &tokens);
+
+        extend_instance_ttl(&env);
+
+        env.events().publish((symbol_short!("tok_add"),), token);
+
+        Ok(())
+    }
+
+    /// Remove a token from the set counted by `get_total_value`. Restricted to admin.
+    pub fn remove_token(env: Env, admin: Address, token: Address) -> Result<(), TreasuryError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut tokens = get_tokens(&env);
+        let index = tokens
+            .first_index_of(&token)
+            .ok_or(TreasuryError::TokenNotTracked)?;
+        tokens.remove(index);
+        set_tokens(&env, &tokens);
+
+        extend_instance_ttl(&env);
+
+        env.events().publish((symbol_short!("tok_rm"),), token);
+
+        Ok(())
+    }
+
+    /// Set a spending budget in the oracle's reference unit. Restricted to admin.
+    /// Executed withdrawals are valued at execution time and may not exceed
+    /// `limit` within any `period` seconds. Requires an oracle to be configured.
+    pub fn set_budget(
+        env: Env,
+        admin: Address,
+        limit: i128,
+        period: u64,
+    ) -> Result<(), TreasuryError> {
+        Self::require_admin(&env, &admin)?;
+        if limit <= 0 || period == 0 {
+            return Err(TreasuryError::InvalidAmount);
+        }
+        if get_oracle(&env).is_none() {
+            return Err(TreasuryError::OracleNotSet);
+        }
+
+        set_budget(
+            &env,
+            &Budget {
+                limit,
+                period,
+                period_start: env.ledger().timestamp(),
+                spent: 0,
+            },
+        );
+
+        extend_instance_ttl(&env);
+
+        env.events().publish((symbol_short!("budget"),), limit);
+
+        Ok(())
+    }
+
     // ── Query Functions ──────────────────────────────────────────────
 
     /// Get the current admin address.
@@ -366,9 +602,53 @@ impl TreasuryContract {
             signers: get_signers(&env),
             threshold: get_threshold(&env),
             proposal_count: get_proposal_count(&env),
+            tiers: get_tiers(&env),
         })
     }
 
+    /// Get the approval tier table for a token. Empty if the token uses the global threshold.
+    pub fn get_tiers(env: Env, token: Address) -> Vec<ApprovalTier> {
+        get_tiers(&env).get(token).unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the price oracle configuration.
+    pub fn get_oracle(env: Env) -> Result<OracleConfig, TreasuryError> {
+        get_oracle(&env).ok_or(TreasuryError::OracleNotSet)
+    }
+
+    /// Get the tokens counted towards the total treasury value.
+    pub fn get_tokens(env: Env) -> Vec<Address> {
+        get_tokens(&env)
+    }
+
+    /// Get the current spending budget, if one is configured.
+    pub fn get_budget(env: Env) -> Option<Budget> {
+        get_budget(&env)
+    }
+
+    /// Value `amount` of `token` in the oracle's reference unit.
+    pub fn get_value(env: Env, token: Address, amount: i128) -> Result<i128, TreasuryError> {
+        Self::value_of(&env, &token, amount)
+    }
+
+    /// Get the combined value of all tracked token balances in the oracle's reference unit.
+    pub fn get_total_value(env: Env) -> Result<i128, TreasuryError> {
+        Self::require_initialized(&env)?;
+        let contract_address = env.current_contract_address();
+
+        let mut total: i128 = 0;
+        for token in get_tokens(&env).iter() {
+            let balance = token::Client::new(&env, &token).balance(&contract_address);
+            if balance > 0 {
+                total = total
+                    .checked_add(Self::value_of(&env, &token, balance)?)
+                    .ok_or(TreasuryError::ValuationOverflow)?;
+            }
+        }
+
+        Ok(total)
+    }
+
     /// Upgrade the contract WASM. Restricted to admin.
     pub fn upgrade(
         env: Env,
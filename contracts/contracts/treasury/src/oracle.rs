@@ -0,0 +1,22 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// A single price quote published by the oracle.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Price of one base unit of the asset in the reference unit, scaled by `10^decimals()`.
+    pub price: i128,
+    /// Ledger timestamp at which the price was recorded.
+    pub timestamp: u64,
+}
+
+/// Interface the treasury expects from a price oracle contract.
+/// Any contract exposing these functions can be configured via `set_oracle`.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Most recent price for `asset`, or `None` if the oracle does not quote it.
+    fn lastprice(env: Env, asset: Address) -> Option<PriceData>;
+
+    /// Number of decimals used to scale `PriceData::price`.
+    fn decimals(env: Env) -> u32;
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Env, Vec};
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
@@ -7,7 +7,7 @@ pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_I
 pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
 pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
-use crate::types::WithdrawalRequest;
+use crate::types::{ApprovalTier, Budget, OracleConfig, WithdrawalRequest};
 
 /// Keys used to store data in the contract's ledger storage.
 #[contracttype]
@@ -22,6 +22,14 @@ pub enum DataKey {
     ProposalCount,
     /// A specific withdrawal request — stored in Persistent storage.
     Withdrawal(u32),
+    /// The price oracle configuration — stored in Instance storage.
+    Oracle,
+    /// Tokens counted towards the total treasury value — stored in Instance storage.
+    Tokens,
+    /// The reference-unit spending budget — stored in Instance storage.
+    Budget,
+    /// Per-token approval tier tables — stored in Instance storage.
+    Tiers,
 }
 
 // ── Admin helpers ────────────────────────────────────────────────
@@ -60,6 +68,19 @@ pub fn set_threshold(env: &Env, threshold: u32) {
         .set(&DataKey::Threshold, &threshold);
 }
 
+// ── Approval tier helpers ────────────────────────────────────────
+
+pub fn get_tiers(env: &Env) -> Map<Address, Vec<ApprovalTier>> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Tiers)
+        .unwrap_or(Map::new(env))
+}
+
+pub fn set_tiers(env: &Env, tiers: &Map<Address, Vec<ApprovalTier>>) {
+    env.storage().instance().set(&DataKey::Tiers, tiers);
+}
+
 // ── Proposal count helpers ───────────────────────────────────────
 
 pub fn get_proposal_count(env: &Env) -> u32 {
@@ -87,6 +108,39 @@ pub fn set_withdrawal(env: &Env, id: u32, request: &WithdrawalRequest) {
         .set(&DataKey::Withdrawal(id), request);
 }
 
+// ── Oracle helpers ──────────────────────────────────────────────
+
+pub fn get_oracle(env: &Env) -> Option<OracleConfig> {
+    env.storage().instance().get(&DataKey::Oracle)
+}
+
+pub fn set_oracle(env: &Env, config: &OracleConfig) {
+    env.storage().instance().set(&DataKey::Oracle, config);
+}
+
+// ── Tracked token helpers ───────────────────────────────────────
+
+pub fn get_tokens(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Tokens)
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_tokens(env: &Env, tokens: &Vec<Address>) {
+    env.storage().instance().set(&DataKey::Tokens, tokens);
+}
+
+// ── Budget helpers ──────────────────────────────────────────────
+
+pub fn get_budget(env: &Env) -> Option<Budget> {
+    env.storage().instance().get(&DataKey::Budget)
+}
+
+pub fn set_budget(env: &Env, budget: &Budget) {
+    env.storage().instance().set(&DataKey::Budget, budget);
+}
+
 // ── TTL helpers ──────────────────────────────────────────────────
 
 pub fn extend_instance_ttl(env: &Env) {
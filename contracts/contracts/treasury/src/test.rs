@@ -2,7 +2,7 @@
 
 use super::*;
 use soroban_sdk::{symbol_short, testutils::Address as _, token, Address, Env, Vec};
-use types::WithdrawalStatus;
+use types::{ApprovalTier, WithdrawalStatus};
 
 fn setup_env() -> (Env, Address, TreasuryContractClient<'static>) {
     let env = Env::default();
@@ -275,6 +275,135 @@ fn test_invalid_threshold_exceeds_signers_at_init() {
     client.initialize(&admin, &signers, &3);
 }
 
+fn three_signer_tiers(env: &Env) -> Vec<ApprovalTier> {
+    let mut tiers = Vec::new(env);
+    tiers.push_back(ApprovalTier {
+        max_amount: 1_000,
+        approvals: 1,
+    });
+    tiers.push_back(ApprovalTier {
+        max_amount: 100_000,
+        approvals: 2,
+    });
+    tiers
+}
+
+#[test]
+fn test_tiered_approval_thresholds() {
+    let (env, admin, client) = setup_env();
+    let signer1 = Address::generate(&env);
+    let signer2 = Address::generate(&env);
+    let signer3 = Address::generate(&env);
+    let token = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer1.clone());
+    signers.push_back(signer2.clone());
+    signers.push_back(signer3.clone());
+
+    client.initialize(&admin, &signers, &3);
+    client.set_tiers(&admin, &token, &three_signer_tiers(&env));
+    assert_eq!(client.get_tiers(&token), three_signer_tiers(&env));
+    assert_eq!(
+        client.get_config().tiers.get(token.clone()).unwrap(),
+        three_signer_tiers(&env)
+    );
+
+    // Small amount: the proposer's approval is enough
+    let small = client.create_withdrawal(&signer1, &token, &recipient, &500, &symbol_short!("ops"));
+    assert_eq!(client.get_withdrawal(&small).status, WithdrawalStatus::Approved);
+
+    // Mid amount: two approvals
+    let mid = client.create_withdrawal(&signer1, &token, &recipient, &5_000, &symbol_short!("ops"));
+    assert_eq!(client.get_withdrawal(&mid).status, WithdrawalStatus::Pending);
+    client.approve_withdrawal(&signer2, &mid);
+    assert_eq!(client.get_withdrawal(&mid).status, WithdrawalStatus::Approved);
+
+    // Above the highest tier: global threshold of three
+    let large =
+        client.create_withdrawal(&signer1, &token, &recipient, &500_000, &symbol_short!("ops"));
+    client.approve_withdrawal(&signer2, &large);
+    assert_eq!(client.get_withdrawal(&large).status, WithdrawalStatus::Pending);
+    client.approve_withdrawal(&signer3, &large);
+    assert_eq!(client.get_withdrawal(&large).status, WithdrawalStatus::Approved);
+
+    // Clearing the table reverts to the global threshold
+    client.set_tiers(&admin, &token, &Vec::new(&env));
+    assert_eq!(client.get_tiers(&token).len(), 0);
+}
+
+#[test]
+fn test_amount_above_tiers_needs_at_least_top_tier_approvals() {
+    let (env, admin, client) = setup_env();
+    let signer1 = Address::generate(&env);
+    let signer2 = Address::generate(&env);
+    let token = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer1.clone());
+    signers.push_back(signer2.clone());
+
+    client.initialize(&admin, &signers, &1);
+    client.set_tiers(&admin, &token, &three_signer_tiers(&env));
+
+    // The global threshold of one is below the top tier, so the top tier applies
+    let large =
+        client.create_withdrawal(&signer1, &token, &recipient, &500_000, &symbol_short!("ops"));
+    assert_eq!(client.get_withdrawal(&large).status, WithdrawalStatus::Pending);
+    client.approve_withdrawal(&signer2, &large);
+    assert_eq!(client.get_withdrawal(&large).status, WithdrawalStatus::Approved);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_tiers_must_be_increasing() {
+    let (env, admin, client) = setup_env();
+    let token = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    signers.push_back(Address::generate(&env));
+
+    client.initialize(&admin, &signers, &2);
+
+    let mut tiers = three_signer_tiers(&env);
+    tiers.push_back(ApprovalTier {
+        max_amount: 50_000,
+        approvals: 2,
+    });
+    client.set_tiers(&admin, &token, &tiers);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_tier_exceeding_signers_rejected() {
+    let (env, admin, client) = setup_env();
+    let token = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+
+    client.initialize(&admin, &signers, &1);
+
+    client.set_tiers(&admin, &token, &three_signer_tiers(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_remove_signer_keeps_tiers_reachable() {
+    let (env, admin, client) = setup_env();
+    let signer1 = Address::generate(&env);
+    let signer2 = Address::generate(&env);
+    let token = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer1.clone());
+    signers.push_back(signer2.clone());
+
+    client.initialize(&admin, &signers, &1);
+    client.set_tiers(&admin, &token, &three_signer_tiers(&env));
+
+    // The 2-approval tier would become unreachable
+    client.remove_signer(&admin, &signer2);
+}
+
 fn create_token_contract<'a>(e: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
     let contract_addr = e
         .register_stellar_asset_contract_v2(admin.clone())
@@ -366,3 +495,206 @@ fn test_execute_withdrawal_insufficient_balance() {
 
     client.execute_withdrawal(&signer1, &proposal_id);
 }
+
+// ── Price oracle ─────────────────────────────────────────────────
+
+mod mock_oracle {
+    use crate::oracle::PriceData;
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Minimal oracle used in tests — prices are set directly and quoted with 7 decimals.
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set_price(env: Env, asset: Address, price: i128, timestamp: u64) {
+            env.storage()
+                .instance()
+                .set(&asset, &PriceData { price, timestamp });
+        }
+
+        pub fn lastprice(env: Env, asset: Address) -> Option<PriceData> {
+            env.storage().instance().get(&asset)
+        }
+
+        pub fn decimals(_env: Env) -> u32 {
+            7
+        }
+    }
+}
+
+use mock_oracle::{MockOracle, MockOracleClient};
+use soroban_sdk::testutils::Ledger;
+
+const PRICE_ONE: i128 = 10_000_000;
+
+fn setup_oracle<'a>(
+    env: &Env,
+    client: &TreasuryContractClient,
+    admin: &Address,
+) -> MockOracleClient<'a> {
+    let oracle_id = env.register(MockOracle, ());
+    client.set_oracle(admin, &oracle_id, &600);
+    MockOracleClient::new(env, &oracle_id)
+}
+
+#[test]
+fn test_get_value_and_total_value() {
+    let (env, admin, client) = setup_env();
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.initialize(&admin, &signers, &1);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+    let oracle = setup_oracle(&env, &client, &admin);
+
+    let xlm = create_token_contract(&env, &Address::generate(&env));
+    let usdc = create_token_contract(&env, &Address::generate(&env));
+    xlm.mint(&client.address, &1000);
+    usdc.mint(&client.address, &500);
+
+    // 1 XLM unit = 0.1 reference units, 1 USDC unit = 1 reference unit
+    oracle.set_price(&xlm.address, &(PRICE_ONE / 10), &1000);
+    oracle.set_price(&usdc.address, &PRICE_ONE, &1000);
+
+    assert_eq!(client.get_value(&xlm.address, &1000), 100);
+
+    client.add_token(&admin, &xlm.address);
+    client.add_token(&admin, &usdc.address);
+    assert_eq!(client.get_tokens().len(), 2);
+    assert_eq!(client.get_total_value(), 600);
+
+    client.remove_token(&admin, &xlm.address);
+    assert_eq!(client.get_total_value(), 500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_get_value_without_oracle() {
+    let (env, admin, client) = setup_env();
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.initialize(&admin, &signers, &1);
+
+    client.get_value(&Address::generate(&env), &1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_get_value_missing_price() {
+    let (env, admin, client) = setup_env();
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.initialize(&admin, &signers, &1);
+    setup_oracle(&env, &client, &admin);
+
+    client.get_value(&Address::generate(&env), &1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_get_value_stale_price() {
+    let (env, admin, client) = setup_env();
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.initialize(&admin, &signers, &1);
+    let oracle = setup_oracle(&env, &client, &admin);
+
+    let token = Address::generate(&env);
+    oracle.set_price(&token, &PRICE_ONE, &1000);
+
+    // Max age is 600 seconds
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1601;
+    });
+
+    client.get_value(&token, &1000);
+}
+
+#[test]
+fn test_budget_limits_execution_and_resets_each_period() {
+    let (env, admin, client) = setup_env();
+    let signer1 = Address::generate(&env);
+    let signer2 = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer1.clone());
+    signers.push_back(signer2.clone());
+    client.initialize(&admin, &signers, &2);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+    let oracle = setup_oracle(&env, &client, &admin);
+    let token_admin_client = create_token_contract(&env, &Address::generate(&env));
+    let token = token_admin_client.address.clone();
+    token_admin_client.mint(&client.address, &10_000);
+    oracle.set_price(&token, &(PRICE_ONE * 2), &1000);
+
+    // 1000 reference units per day
+    client.set_budget(&admin, &1000, &86_400);
+
+    let first =
+        client.create_withdrawal(&signer1, &token, &recipient, &400, &symbol_short!("ops"));
+    client.approve_withdrawal(&signer2, &first);
+    client.execute_withdrawal(&signer1, &first);
+    assert_eq!(client.get_budget().unwrap().spent, 800);
+
+    let second =
+        client.create_withdrawal(&signer1, &token, &recipient, &200, &symbol_short!("ops"));
+    client.approve_withdrawal(&signer2, &second);
+    let result = client.try_execute_withdrawal(&signer1, &second);
+    assert_eq!(result, Err(Ok(TreasuryError::BudgetExceeded)));
+
+    // A new period starts the next day
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000 + 86_400;
+    });
+    oracle.set_price(&token, &(PRICE_ONE * 2), &(1000 + 86_400));
+    client.execute_withdrawal(&signer1, &second);
+    assert_eq!(client.get_budget().unwrap().spent, 400);
+}
+
+#[test]
+fn test_budget_with_unbounded_period_does_not_trap() {
+    let (env, admin, client) = setup_env();
+    let signer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer.clone());
+    client.initialize(&admin, &signers, &1);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+    let oracle = setup_oracle(&env, &client, &admin);
+    let token_admin_client = create_token_contract(&env, &Address::generate(&env));
+    let token = token_admin_client.address.clone();
+    token_admin_client.mint(&client.address, &10_000);
+    oracle.set_price(&token, &PRICE_ONE, &1000);
+
+    client.set_budget(&admin, &i128::MAX, &u64::MAX);
+
+    let proposal =
+        client.create_withdrawal(&signer, &token, &recipient, &400, &symbol_short!("ops"));
+    client.execute_withdrawal(&signer, &proposal);
+    assert_eq!(client.get_budget().unwrap().spent, 400);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_get_value_overflow() {
+    let (env, admin, client) = setup_env();
+    let mut signers = Vec::new(&env);
+    signers.push_back(Address::generate(&env));
+    client.initialize(&admin, &signers, &1);
+    let oracle = setup_oracle(&env, &client, &admin);
+
+    let token = Address::generate(&env);
+    oracle.set_price(&token, &i128::MAX, &0);
+
+    client.get_value(&token, &2);
+}
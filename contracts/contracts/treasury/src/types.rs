@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Symbol, Vec};
+use soroban_sdk::{contracttype, Address, Map, Symbol, Vec};
 
 /// Represents the status of a withdrawal request in the multi-sig flow.
 #[contracttype]
@@ -50,4 +50,43 @@ pub struct TreasuryConfig {
     pub threshold: u32,
     /// Total number of proposals created.
     pub proposal_count: u32,
+    /// Amount-tiered approval thresholds, keyed by token.
+    pub tiers: Map<Address, Vec<ApprovalTier>>,
+}
+
+/// One row of a token's approval tier table.
+/// Withdrawals up to and including `max_amount` need `approvals` signers;
+/// amounts above the highest tier need the global threshold or the highest tier's
+/// approvals, whichever is greater.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalTier {
+    /// Largest withdrawal amount (in token units) covered by this tier.
+    pub max_amount: i128,
+    /// Number of approvals required for withdrawals in this tier.
+    pub approvals: u32,
+}
+
+/// Price oracle configuration used to value holdings in the reference unit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    /// Address of the price oracle contract.
+    pub oracle: Address,
+    /// Maximum age (in seconds) of a price before it is considered stale.
+    pub max_price_age: u64,
+}
+
+/// A spending budget denominated in the oracle's reference unit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Budget {
+    /// Maximum value that may be withdrawn within a single period.
+    pub limit: i128,
+    /// Length of a budget period in seconds.
+    pub period: u64,
+    /// Ledger timestamp at which the current period started.
+    pub period_start: u64,
+    /// Value already withdrawn in the current period.
+    pub spent: i128,
 }